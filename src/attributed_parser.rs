@@ -11,12 +11,43 @@ pub enum ParsedItem {
 
 /// Parses any syntax item that was annotated with the `enum_dispatch` attribute and returns its
 /// itemized results.
-pub fn parse_attributed(item: proc_macro2::TokenStream) -> Result<ParsedItem, ()> {
-    if let Ok(enumdef) = syn::parse2(item.clone()) {
-        Ok(ParsedItem::EnumDispatch(enumdef))
-    } else if let Ok(traitdef) = syn::parse2(item) {
-        Ok(ParsedItem::Trait(traitdef))
-    } else {
-        Err(())
+///
+/// The kind of item is determined before parsing it, so that any errors refer to the item that
+/// was actually annotated.
+pub fn parse_attributed(item: proc_macro2::TokenStream) -> Result<ParsedItem, syn::Error> {
+    let parser = |input: syn::parse::ParseStream| {
+        // Look past any attributes and visibility to find out which kind of item this is.
+        let fork = input.fork();
+        fork.call(syn::Attribute::parse_outer)?;
+        fork.parse::<syn::Visibility>()?;
+        if fork.peek(syn::Token![enum]) {
+            Ok(ParsedItem::EnumDispatch(input.parse()?))
+        } else if fork.peek(syn::Token![trait])
+            || fork.peek(syn::Token![unsafe]) && fork.peek2(syn::Token![trait])
+            || fork.peek(syn::Token![auto])
+        {
+            Ok(ParsedItem::Trait(input.parse()?))
+        } else {
+            Err(fork.error("#[enum_dispatch] can only be used on traits or enums"))
+        }
+    };
+    syn::parse::Parser::parse2(parser, item)
+}
+
+impl ParsedItem {
+    /// Removes all remaining `#[enum_dispatch]` attributes from the parsed item and returns them.
+    ///
+    /// Only the outermost attribute is expanded as a macro, with any others still attached to the
+    /// item. Taking them lets their arguments be handled in a single expansion.
+    pub fn take_enum_dispatch_attrs(&mut self) -> Vec<syn::Attribute> {
+        let attrs = match self {
+            ParsedItem::Trait(traitdef) => &mut traitdef.attrs,
+            ParsedItem::EnumDispatch(enumdef) => &mut enumdef.attrs,
+        };
+        let (taken, kept) = std::mem::take(attrs).into_iter().partition(|attr| {
+            matches!(attr.path().segments.last(), Some(segment) if segment.ident == "enum_dispatch")
+        });
+        *attrs = kept;
+        taken
     }
 }
@@ -0,0 +1,170 @@
+//! Provides a catalogue of well-known traits from the standard library that can be linked by
+//! enums without being declared under `#[enum_dispatch]`.
+//!
+//! Foreign traits can't be annotated, so their definitions will never be stored in the cache.
+//! Instead, this module holds mirror definitions of their signatures, which are used to generate
+//! impls of the real traits. Only methods that are worth forwarding are mirrored, i.e. required
+//! methods, along with provided methods that implementors commonly specialize. Any other provided
+//! methods use their default implementations.
+//!
+//! Traits available in `core` are referred to with `::core::` paths, so that they can be used in
+//! `no_std` crates. `Error` is the exception, since it was only moved to `core` in Rust 1.81.
+//!
+//! A trait must be referred to by a path of at least two segments, like `fmt::Display`, to be
+//! recognized. Single identifiers like `Display` are left to user-defined traits of the same name,
+//! which may not have been expanded yet when the enum is.
+
+use crate::syn_utils::with_arguments;
+
+/// A trait from the standard library that can be implemented by enums.
+struct BuiltinTrait {
+    /// Paths that can be used to refer to the trait in an `#[enum_dispatch(...)]` attribute,
+    /// without a leading `::`.
+    names: &'static [&'static str],
+    /// The full path of the trait, used in generated impls.
+    path: &'static str,
+    /// A mirror of the trait's definition.
+    def: &'static str,
+}
+
+const BUILTIN_TRAITS: &[BuiltinTrait] = &[
+    BuiltinTrait {
+        names: &["fmt::Display", "core::fmt::Display", "std::fmt::Display"],
+        path: "::core::fmt::Display",
+        def: "trait Display {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result;
+        }",
+    },
+    BuiltinTrait {
+        names: &["fmt::Debug", "core::fmt::Debug", "std::fmt::Debug"],
+        path: "::core::fmt::Debug",
+        def: "trait Debug {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result;
+        }",
+    },
+    BuiltinTrait {
+        names: &["error::Error", "std::error::Error"],
+        path: "::std::error::Error",
+        def: "trait Error {
+            fn source(&self) -> ::core::option::Option<&(dyn ::std::error::Error + 'static)>;
+        }",
+    },
+    BuiltinTrait {
+        names: &["iter::Iterator", "core::iter::Iterator", "std::iter::Iterator"],
+        path: "::core::iter::Iterator",
+        def: "trait Iterator {
+            type Item;
+            fn next(&mut self) -> ::core::option::Option<Self::Item>;
+            fn size_hint(&self) -> (usize, ::core::option::Option<usize>);
+        }",
+    },
+    BuiltinTrait {
+        names: &[
+            "iter::DoubleEndedIterator",
+            "core::iter::DoubleEndedIterator",
+            "std::iter::DoubleEndedIterator",
+        ],
+        path: "::core::iter::DoubleEndedIterator",
+        def: "trait DoubleEndedIterator {
+            fn next_back(&mut self) -> ::core::option::Option<<Self as ::core::iter::Iterator>::Item>;
+        }",
+    },
+    BuiltinTrait {
+        names: &[
+            "iter::ExactSizeIterator",
+            "core::iter::ExactSizeIterator",
+            "std::iter::ExactSizeIterator",
+        ],
+        path: "::core::iter::ExactSizeIterator",
+        def: "trait ExactSizeIterator {
+            fn len(&self) -> usize;
+        }",
+    },
+    BuiltinTrait {
+        names: &["io::Read", "std::io::Read"],
+        path: "::std::io::Read",
+        def: "trait Read {
+            fn read(&mut self, buf: &mut [u8]) -> ::std::io::Result<usize>;
+            fn read_vectored(&mut self, bufs: &mut [::std::io::IoSliceMut<'_>]) -> ::std::io::Result<usize>;
+            fn read_to_end(&mut self, buf: &mut ::std::vec::Vec<u8>) -> ::std::io::Result<usize>;
+            fn read_to_string(&mut self, buf: &mut ::std::string::String) -> ::std::io::Result<usize>;
+            fn read_exact(&mut self, buf: &mut [u8]) -> ::std::io::Result<()>;
+        }",
+    },
+    BuiltinTrait {
+        names: &["io::Write", "std::io::Write"],
+        path: "::std::io::Write",
+        def: "trait Write {
+            fn write(&mut self, buf: &[u8]) -> ::std::io::Result<usize>;
+            fn write_vectored(&mut self, bufs: &[::std::io::IoSlice<'_>]) -> ::std::io::Result<usize>;
+            fn flush(&mut self) -> ::std::io::Result<()>;
+            fn write_all(&mut self, buf: &[u8]) -> ::std::io::Result<()>;
+            fn write_fmt(&mut self, fmt: ::core::fmt::Arguments<'_>) -> ::std::io::Result<()>;
+        }",
+    },
+    BuiltinTrait {
+        names: &["io::Seek", "std::io::Seek"],
+        path: "::std::io::Seek",
+        def: "trait Seek {
+            fn seek(&mut self, pos: ::std::io::SeekFrom) -> ::std::io::Result<u64>;
+            fn stream_position(&mut self) -> ::std::io::Result<u64>;
+        }",
+    },
+    BuiltinTrait {
+        names: &["hash::Hash", "core::hash::Hash", "std::hash::Hash"],
+        path: "::core::hash::Hash",
+        def: "trait Hash {
+            fn hash<H: ::core::hash::Hasher>(&self, state: &mut H);
+        }",
+    },
+    BuiltinTrait {
+        names: &["fmt::Write", "core::fmt::Write", "std::fmt::Write"],
+        path: "::core::fmt::Write",
+        def: "trait Write {
+            fn write_str(&mut self, s: &str) -> ::core::fmt::Result;
+            fn write_char(&mut self, c: char) -> ::core::fmt::Result;
+            fn write_fmt(&mut self, args: ::core::fmt::Arguments<'_>) -> ::core::fmt::Result;
+        }",
+    },
+    BuiltinTrait {
+        names: &["future::Future", "core::future::Future", "std::future::Future"],
+        path: "::core::future::Future",
+        def: "trait Future {
+            type Output;
+            fn poll(
+                self: ::core::pin::Pin<&mut Self>,
+                cx: &mut ::core::task::Context<'_>,
+            ) -> ::core::task::Poll<Self::Output>;
+        }",
+    },
+];
+
+/// Looks up a trait from the standard library by the path used to link to it, returning a mirror
+/// of its definition and the full path to use in generated impls. Any generic arguments given for
+/// the trait in the link, like `Item = u8`, are kept on the returned path.
+pub fn lookup(path: &syn::Path) -> Option<(syn::ItemTrait, syn::Path)> {
+    let last = path.segments.last().unwrap();
+    if path
+        .segments
+        .iter()
+        .take(path.segments.len() - 1)
+        .any(|segment| !segment.arguments.is_none())
+    {
+        return None;
+    }
+    let name = path
+        .segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect::<Vec<_>>()
+        .join("::");
+    BUILTIN_TRAITS
+        .iter()
+        .find(|builtin| builtin.names.contains(&name.as_str()))
+        .map(|builtin| {
+            (
+                syn::parse_str(builtin.def).unwrap(),
+                with_arguments(syn::parse_str(builtin.path).unwrap(), last.arguments.clone()),
+            )
+        })
+}
@@ -7,6 +7,12 @@
 //! statically. Unfortunately, doing so strips any related `Span` information, preventing error
 //! messages from being as informative as they could be. For now, it seems this is the best option
 //! available.
+//!
+//! Procedural macros also have no way of knowing which module an item is defined in. Items are
+//! keyed by their name alone, but can optionally be declared to live in a module with
+//! `#[enum_dispatch(module = ...)]`. Links that refer to an item with an absolute path (e.g.
+//! `crate::engine::Apply`) are then only fulfilled by an item declared in the same module, which
+//! allows same-named items in different modules to coexist.
 use quote::ToTokens;
 
 use once_cell::sync::Lazy;
@@ -15,6 +21,7 @@ use std::collections::{HashMap, HashSet};
 use std::sync::Mutex;
 
 use crate::enum_dispatch_item;
+use crate::syn_utils::with_arguments;
 
 /// Uniquely identifies a trait or an enum. This is based on its name and number of arguments.
 #[derive(PartialEq, Eq, Hash, Clone)]
@@ -33,121 +40,465 @@ impl UniqueItemId {
     }
 }
 
+/// A cached trait or enum definition, along with the module it was declared to be in, if any.
+struct CachedDef {
+    module: Option<String>,
+    def: String,
+    /// The path that generated code should always use to refer to the item, if any. This is used
+    /// for mirrors of foreign traits, which aren't defined where their mirror is declared.
+    path: Option<String>,
+}
+
+/// One side of a link between a trait and an enum, stored under the id of the item on this side.
+#[derive(Clone)]
+struct Link {
+    /// The module that the item on this side was referred to with or declared in, if known.
+    module: Option<String>,
+    /// The path that the item on this side was referred to with in the link, if it is the item
+    /// named in the attribute.
+    path: Option<String>,
+    /// The item on the other side of the link.
+    other: UniqueItemId,
+    /// The module that the item on the other side was referred to with or declared in, if known.
+    other_module: Option<String>,
+    /// A path that can be used to refer to the item on the other side from the definition site of
+    /// the item on this side, if it differs from the plain name of the other item. This includes
+    /// any generic arguments given for the other item in the link.
+    other_path: Option<String>,
+    /// Generic arguments given for the item on this side in the link, e.g. `<'a, u32>`, if any.
+    arguments: Option<String>,
+}
+
+/// Identifies an item along with the module it was declared to be in, if any.
+type ModuleItemId = (UniqueItemId, Option<String>);
+
 // Magical storage for trait definitions so that they can be used when parsing other syntax
 // structures.
-static TRAIT_DEFS: Lazy<Mutex<HashMap<UniqueItemId, String>>> =
+static TRAIT_DEFS: Lazy<Mutex<HashMap<UniqueItemId, Vec<CachedDef>>>> =
     Lazy::new(|| Mutex::new(HashMap::new()));
-static ENUM_DEFS: Lazy<Mutex<HashMap<UniqueItemId, String>>> =
+static ENUM_DEFS: Lazy<Mutex<HashMap<UniqueItemId, Vec<CachedDef>>>> =
     Lazy::new(|| Mutex::new(HashMap::new()));
-static DEFERRED_LINKS: Lazy<Mutex<HashMap<UniqueItemId, Vec<UniqueItemId>>>> =
+static DEFERRED_LINKS: Lazy<Mutex<HashMap<UniqueItemId, Vec<Link>>>> =
     Lazy::new(|| Mutex::new(HashMap::new()));
-static ENUM_CONVERSION_IMPLS_DEFS: Lazy<Mutex<HashSet<UniqueItemId>>> =
+static ENUM_CONVERSION_IMPLS_DEFS: Lazy<Mutex<HashSet<ModuleItemId>>> =
+    Lazy::new(|| Mutex::new(HashSet::new()));
+static ENUM_PIN_GUARDS_DEFS: Lazy<Mutex<HashSet<ModuleItemId>>> =
     Lazy::new(|| Mutex::new(HashSet::new()));
+static ENUM_CONST_ACCESSORS: Lazy<Mutex<HashMap<ModuleItemId, HashMap<String, String>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Converts a module path to the string used to identify it in the cache.
+fn module_string(module: &syn::Path) -> String {
+    module.to_token_stream().to_string()
+}
+
+/// Returns the module prefix of a path referring to an item, if it is absolute. Relative paths
+/// like `super::Trait` can't be compared against declared modules, so they are ignored.
+fn absolute_module(path: &syn::Path) -> Option<String> {
+    if path.segments[0].ident != "crate" || path.segments.len() < 2 {
+        return None;
+    }
+    let mut module = path.clone();
+    module.segments.pop();
+    module.segments.pop_punct();
+    Some(module_string(&module))
+}
+
+/// Returns true if an item that is known by `a` could be the same item that is known by `b`.
+fn modules_compatible(a: &Option<String>, b: &Option<String>) -> bool {
+    a.is_none() || b.is_none() || a == b
+}
+
+/// Stores a definition, replacing any previous definition declared in the same module.
+fn store_def(
+    defs: &Mutex<HashMap<UniqueItemId, Vec<CachedDef>>>,
+    uid: UniqueItemId,
+    module: Option<&syn::Path>,
+    def: String,
+    path: Option<&syn::Path>,
+) {
+    let module = module.map(module_string);
+    let path = path.map(|path| path.into_token_stream().to_string());
+    let mut defs = defs.lock().unwrap();
+    let entries = defs.entry(uid).or_default();
+    entries.retain(|entry| entry.module != module);
+    entries.push(CachedDef { module, def, path });
+}
+
+/// Finds the definition that best matches a module. An exact match is preferred, followed by the
+/// most recent compatible definition. The definition is returned along with its fixed path, if
+/// any.
+fn select_def(
+    defs: &Mutex<HashMap<UniqueItemId, Vec<CachedDef>>>,
+    uid: &UniqueItemId,
+    module: &Option<String>,
+) -> Option<(String, Option<String>)> {
+    let defs = defs.lock().unwrap();
+    let entries = defs.get(uid)?;
+    entries
+        .iter()
+        .find(|entry| entry.module == *module)
+        .or_else(|| {
+            entries
+                .iter()
+                .rev()
+                .find(|entry| modules_compatible(&entry.module, module))
+        })
+        .map(|entry| (entry.def.clone(), entry.path.clone()))
+}
 
 /// Store a trait definition for future reference.
-pub fn cache_trait(item: syn::ItemTrait) {
+pub fn cache_trait(item: syn::ItemTrait, module: Option<&syn::Path>) {
     let num_generics = crate::supported_generics::num_supported_generics(&item.generics);
     let uid = UniqueItemId::new(item.ident.to_string(), num_generics);
-    TRAIT_DEFS
-        .lock()
-        .unwrap()
-        .insert(uid, item.into_token_stream().to_string());
+    store_def(&TRAIT_DEFS, uid, module, item.into_token_stream().to_string(), None);
+}
+
+/// Store a mirror of a trait defined in another crate for future reference. Generated code will
+/// always refer to the trait using `path`.
+pub fn cache_foreign_trait(item: syn::ItemTrait, path: &syn::Path) {
+    let num_generics = crate::supported_generics::num_supported_generics(&item.generics);
+    let uid = UniqueItemId::new(item.ident.to_string(), num_generics);
+    store_def(&TRAIT_DEFS, uid, None, item.into_token_stream().to_string(), Some(path));
 }
 
 /// Store an enum definition for future reference.
-pub fn cache_enum_dispatch(item: enum_dispatch_item::EnumDispatchItem) {
+pub fn cache_enum_dispatch(item: enum_dispatch_item::EnumDispatchItem, module: Option<&syn::Path>) {
     let num_generics = crate::supported_generics::num_supported_generics(&item.generics);
     let uid = UniqueItemId::new(item.ident.to_string(), num_generics);
-    ENUM_DEFS
+    store_def(&ENUM_DEFS, uid, module, item.into_token_stream().to_string(), None);
+}
+
+/// Store whether a From/TryInto definition has been defined once for an enum.
+pub fn cache_enum_conversion_impls_defined(
+    item: syn::Ident,
+    num_generics: usize,
+    module: Option<&syn::Path>,
+) {
+    let uid = UniqueItemId::new(item.to_string(), num_generics);
+    ENUM_CONVERSION_IMPLS_DEFS
         .lock()
         .unwrap()
-        .insert(uid, item.into_token_stream().to_string());
+        .insert((uid, module.map(module_string)));
 }
 
-/// Store whether a From/TryInto definition has been defined once for an enum.
-pub fn cache_enum_conversion_impls_defined(item: syn::Ident, num_generics: usize) {
+/// Store whether the guards for pinned receivers have been defined once for an enum.
+pub fn cache_enum_pin_guards_defined(
+    item: syn::Ident,
+    num_generics: usize,
+    module: Option<&syn::Path>,
+) {
     let uid = UniqueItemId::new(item.to_string(), num_generics);
-    ENUM_CONVERSION_IMPLS_DEFS.lock().unwrap().insert(uid);
+    ENUM_PIN_GUARDS_DEFS
+        .lock()
+        .unwrap()
+        .insert((uid, module.map(module_string)));
+}
+
+/// Store the names of the accessor methods generated for the associated constants of an enum's
+/// linked traits, each mapped to the constant it returns.
+pub fn cache_enum_const_accessors(
+    item: syn::Ident,
+    num_generics: usize,
+    module: Option<&syn::Path>,
+    accessors: HashMap<String, String>,
+) {
+    let uid = UniqueItemId::new(item.to_string(), num_generics);
+    ENUM_CONST_ACCESSORS
+        .lock()
+        .unwrap()
+        .insert((uid, module.map(module_string)), accessors);
 }
 
 /// Cache a "link" to be fulfilled once the needed definition is also cached.
 ///
 /// The number of generic arguments is also cached and must be equal in order to fulfill a link,
-/// however the actual generic arguments themselves may have different names.
+/// however the actual generic arguments themselves may have different names. The arguments are
+/// kept, so that they can be substituted for the generic parameters of the needed item.
+///
+/// `needed` is the path used in the `#[enum_dispatch(...)]` attribute of the `cached` item, which
+/// may optionally have been declared to be in `cached_module`.
 pub fn defer_link(
-    (needed, needed_num_generics): (&::proc_macro2::Ident, usize),
+    (needed, needed_num_generics): (&syn::Path, usize),
     (cached, cached_num_generics): (&::proc_macro2::Ident, usize),
+    cached_module: Option<&syn::Path>,
 ) {
-    use std::collections::hash_map::Entry;
-
-    let (needed, cached) = (
-        UniqueItemId::new(needed.to_string(), needed_num_generics),
+    let needed_name = &needed.segments.last().unwrap().ident;
+    let (needed_id, cached_id) = (
+        UniqueItemId::new(needed_name.to_string(), needed_num_generics),
         UniqueItemId::new(cached.to_string(), cached_num_generics),
     );
+    let needed_module = absolute_module(needed);
+    let cached_module = cached_module.map(module_string);
+    let needed_arguments = match &needed.segments.last().unwrap().arguments {
+        syn::PathArguments::None => None,
+        arguments => Some(arguments.into_token_stream().to_string()),
+    };
+
+    let mut deferred_links = DEFERRED_LINKS.lock().unwrap();
+    deferred_links.entry(needed_id.clone()).or_default().push(Link {
+        module: needed_module.clone(),
+        path: Some(needed.into_token_stream().to_string()),
+        other: cached_id.clone(),
+        other_module: cached_module.clone(),
+        other_path: cached_module
+            .as_ref()
+            .map(|module| format!("{} :: {}", module, cached)),
+        arguments: needed_arguments,
+    });
+    deferred_links.entry(cached_id).or_default().push(Link {
+        module: cached_module,
+        path: None,
+        other: needed_id,
+        other_module: needed_module,
+        other_path: Some(needed.into_token_stream().to_string()),
+        arguments: None,
+    });
+}
+
+/// Removes and returns all links of the given item that are compatible with the module it is
+/// declared in.
+fn take_links(defname: &::proc_macro2::Ident, num_generic_args: usize, module: &Option<String>) -> Vec<Link> {
+    let uid = UniqueItemId::new(defname.to_string(), num_generic_args);
     let mut deferred_links = DEFERRED_LINKS.lock().unwrap();
-    if deferred_links.contains_key(&needed) {
-        deferred_links
-            .get_mut(&needed)
-            .unwrap()
-            .push(cached.to_owned());
-    } else {
-        deferred_links.insert(needed.to_owned(), vec![cached.to_owned()]);
+    let links = match deferred_links.remove(&uid) {
+        Some(links) => links,
+        None => return vec![],
+    };
+    let (fulfilled, remaining): (Vec<_>, Vec<_>) = links
+        .into_iter()
+        .partition(|link| modules_compatible(&link.module, module));
+    if !remaining.is_empty() {
+        deferred_links.insert(uid, remaining);
+    }
+    fulfilled
+}
+
+/// Parses the path used to refer to the other side of a link, if any. A fixed path for the other
+/// item takes precedence over the path used in the link, but still gets its generic arguments.
+fn other_path(link: &Link, fixed_path: Option<String>) -> Option<syn::Path> {
+    let link_path = link
+        .other_path
+        .as_ref()
+        .map(|path| syn::parse_str::<syn::Path>(path).unwrap());
+    match (fixed_path, link_path) {
+        (Some(fixed_path), link_path) => {
+            let fixed_path = syn::parse_str(&fixed_path).unwrap();
+            let arguments = link_path
+                .map(|path| path.segments.last().unwrap().arguments.clone())
+                .unwrap_or(syn::PathArguments::None);
+            Some(with_arguments(fixed_path, arguments))
+        }
+        (None, link_path) => link_path,
     }
-    if let Entry::Vacant(e) = deferred_links.entry(cached.clone()) {
-        e.insert(vec![needed]);
-    } else {
-        deferred_links.get_mut(&cached).unwrap().push(needed);
+}
+
+/// Parses the generic arguments given for the item on this side of a link.
+fn own_arguments(link: &Link) -> syn::PathArguments {
+    match &link.arguments {
+        Some(arguments) => {
+            syn::PathArguments::AngleBracketed(syn::parse_str(arguments).unwrap())
+        }
+        None => syn::PathArguments::None,
     }
 }
 
 /// Returns a list of all of the trait definitions that were previously linked to the supplied enum
-/// name.
+/// name, along with the path to use to refer to each trait, if it differs from its name, and the
+/// generic arguments given for the enum in the link.
 pub fn fulfilled_by_enum(
     defname: &::proc_macro2::Ident,
     num_generic_args: usize,
-) -> Vec<syn::ItemTrait> {
-    let idents = match DEFERRED_LINKS
-        .lock()
-        .unwrap()
-        .remove_entry(&UniqueItemId::new(defname.to_string(), num_generic_args))
-    {
-        Some((_, links)) => links,
-        None => vec![],
-    };
-    idents
+    module: Option<&syn::Path>,
+) -> Vec<(syn::ItemTrait, Option<syn::Path>, syn::PathArguments)> {
+    let links = take_links(defname, num_generic_args, &module.map(module_string));
+    links
         .iter()
-        .filter_map(
-            |ident_string| TRAIT_DEFS.lock().unwrap().get(ident_string).map(|entry| syn::parse(entry.parse().unwrap()).unwrap())
-        )
+        .filter_map(|link| {
+            select_def(&TRAIT_DEFS, &link.other, &link.other_module).map(|(def, path)| {
+                (syn::parse_str(&def).unwrap(), other_path(link, path), own_arguments(link))
+            })
+        })
         .collect()
 }
 
 /// Returns a list of all of the enum definitions that were previously linked to the supplied trait
-/// name.
+/// name, along with the path to use to refer to each enum, if it differs from its name, and the
+/// generic arguments given for the trait in the link.
 pub fn fulfilled_by_trait(
     defname: &::proc_macro2::Ident,
     num_generic_args: usize,
-) -> Vec<enum_dispatch_item::EnumDispatchItem> {
-    let idents = match DEFERRED_LINKS
-        .lock()
-        .unwrap()
-        .remove_entry(&UniqueItemId::new(defname.to_string(), num_generic_args))
-    {
-        Some((_, links)) => links,
-        None => vec![],
-    };
-    idents
+    module: Option<&syn::Path>,
+) -> Vec<(enum_dispatch_item::EnumDispatchItem, Option<syn::Path>, syn::PathArguments)> {
+    let links = take_links(defname, num_generic_args, &module.map(module_string));
+    links
         .iter()
-        .filter_map(
-            |ident_string| ENUM_DEFS.lock().unwrap().get(ident_string).map(|entry| syn::parse(entry.parse().unwrap()).unwrap())
-        )
+        .filter_map(|link| {
+            select_def(&ENUM_DEFS, &link.other, &link.other_module).map(|(def, path)| {
+                (syn::parse_str(&def).unwrap(), other_path(link, path), own_arguments(link))
+            })
+        })
         .collect()
 }
 
 /// Returns true if From/TryInto was already defined for this enum
-pub fn conversion_impls_def_by_enum(item: &syn::Ident, num_generics: usize) -> bool {
-    ENUM_CONVERSION_IMPLS_DEFS
+pub fn conversion_impls_def_by_enum(
+    item: &syn::Ident,
+    num_generics: usize,
+    module: Option<&syn::Path>,
+) -> bool {
+    ENUM_CONVERSION_IMPLS_DEFS.lock().unwrap().contains(&(
+        UniqueItemId::new(item.to_string(), num_generics),
+        module.map(module_string),
+    ))
+}
+
+/// Returns true if the guards for pinned receivers were already defined for this enum
+pub fn pin_guards_def_by_enum(
+    item: &syn::Ident,
+    num_generics: usize,
+    module: Option<&syn::Path>,
+) -> bool {
+    ENUM_PIN_GUARDS_DEFS.lock().unwrap().contains(&(
+        UniqueItemId::new(item.to_string(), num_generics),
+        module.map(module_string),
+    ))
+}
+
+/// Returns the accessor methods already generated for the associated constants of an enum's linked
+/// traits, so that clashes with the accessors of another trait can be reported.
+pub fn const_accessors_by_enum(
+    item: &syn::Ident,
+    num_generics: usize,
+    module: Option<&syn::Path>,
+) -> HashMap<String, String> {
+    ENUM_CONST_ACCESSORS
+        .lock()
+        .unwrap()
+        .get(&(
+            UniqueItemId::new(item.to_string(), num_generics),
+            module.map(module_string),
+        ))
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// A link from an `#[enum_dispatch(...)]` attribute that has not been fulfilled, because the item
+/// it refers to was never defined.
+pub struct UnresolvedLink {
+    /// The name of the missing item.
+    pub name: String,
+    /// The number of generic arguments the missing item was expected to have.
+    pub num_generics: usize,
+    /// The name of the item whose attribute referred to the missing item.
+    pub linked_from: String,
+    /// The path that the attribute referred to the missing item with.
+    pub path: syn::Path,
+}
+
+/// Returns true if a definition of the given item compatible with the given module was cached.
+fn is_defined(uid: &UniqueItemId, module: &Option<String>) -> bool {
+    select_def(&TRAIT_DEFS, uid, module).is_some() || select_def(&ENUM_DEFS, uid, module).is_some()
+}
+
+/// Returns all links that refer to items that have not been defined, sorted by name.
+pub fn unresolved_links() -> Vec<UnresolvedLink> {
+    let deferred_links = DEFERRED_LINKS.lock().unwrap();
+    let mut unresolved: Vec<UnresolvedLink> = deferred_links
+        .iter()
+        .flat_map(|(uid, links)| {
+            links
+                .iter()
+                .filter(move |link| !is_defined(uid, &link.module))
+                .map(move |link| UnresolvedLink {
+                    name: uid.item_name.clone(),
+                    num_generics: uid.num_generics,
+                    linked_from: link.other.item_name.clone(),
+                    path: syn::parse_str(link.path.as_ref().unwrap_or(&uid.item_name)).unwrap(),
+                })
+        })
+        .collect();
+    unresolved.sort_by(|a, b| {
+        (&a.name, a.num_generics, &a.linked_from).cmp(&(&b.name, b.num_generics, &b.linked_from))
+    });
+    unresolved.dedup_by(|a, b| {
+        (&a.name, a.num_generics, &a.linked_from) == (&b.name, b.num_generics, &b.linked_from)
+    });
+    unresolved
+}
+
+/// Returns the numbers of generic arguments that traits or enums named by the final segment of
+/// `path` were defined with, if none of them match the `num_generics` a link to `path` was given
+/// with. `linked_from_trait` selects whether enums or traits are searched.
+pub fn mismatched_arities(path: &syn::Path, num_generics: usize, linked_from_trait: bool) -> Vec<usize> {
+    let name = path.segments.last().unwrap().ident.to_string();
+    let module = absolute_module(path);
+    let defs = if linked_from_trait { &ENUM_DEFS } else { &TRAIT_DEFS };
+    let defs = defs.lock().unwrap();
+    let mut arities: Vec<usize> = defs
+        .iter()
+        .filter(|(uid, entries)| {
+            uid.item_name == name
+                && entries
+                    .iter()
+                    .any(|entry| modules_compatible(&entry.module, &module))
+        })
+        .map(|(uid, _)| uid.num_generics)
+        .collect();
+    if arities.contains(&num_generics) {
+        return vec![];
+    }
+    arities.sort_unstable();
+    arities
+}
+
+/// Removes and returns links to the given item that were given a different number of generic
+/// arguments than it was defined with, as `UnresolvedLink`s. Only links from enums are considered
+/// for traits, and vice versa.
+pub fn take_mismatched_links(
+    defname: &::proc_macro2::Ident,
+    num_generic_args: usize,
+    module: Option<&syn::Path>,
+    is_trait: bool,
+) -> Vec<UnresolvedLink> {
+    let name = defname.to_string();
+    let module = module.map(module_string);
+    let linking_defs = if is_trait { &ENUM_DEFS } else { &TRAIT_DEFS };
+    let mut mismatched = vec![];
+    let mut deferred_links = DEFERRED_LINKS.lock().unwrap();
+    for (uid, links) in deferred_links.iter_mut() {
+        if uid.item_name != name || uid.num_generics == num_generic_args {
+            continue;
+        }
+        links.retain(|link| {
+            let is_mismatched = modules_compatible(&link.module, &module)
+                && select_def(linking_defs, &link.other, &link.other_module).is_some();
+            if is_mismatched {
+                mismatched.push(UnresolvedLink {
+                    name: name.clone(),
+                    num_generics: uid.num_generics,
+                    linked_from: link.other.item_name.clone(),
+                    path: syn::parse_str(link.path.as_ref().unwrap_or(&name)).unwrap(),
+                });
+            }
+            !is_mismatched
+        });
+    }
+    deferred_links.retain(|_, links| !links.is_empty());
+    mismatched
+}
+
+/// Returns the names and numbers of generic arguments of all traits and enums that have been
+/// defined.
+pub fn defined_items() -> Vec<(String, usize)> {
+    let mut items: Vec<(String, usize)> = TRAIT_DEFS
         .lock()
         .unwrap()
-        .contains(&UniqueItemId::new(item.to_string(), num_generics))
+        .keys()
+        .chain(ENUM_DEFS.lock().unwrap().keys())
+        .map(|uid| (uid.item_name.clone(), uid.num_generics))
+        .collect();
+    items.sort();
+    items.dedup();
+    items
 }
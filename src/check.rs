@@ -0,0 +1,159 @@
+//! Provides diagnostics for links that were never fulfilled.
+//!
+//! A link to an item that is never defined is stored in the cache indefinitely, and no impls are
+//! generated for it. On its own, that only results in confusing "trait not implemented" errors
+//! wherever the missing impls are used. An `enum_dispatch::check!()` invocation, expanded after
+//! all other items, reports those links directly instead.
+//!
+//! Links given the wrong number of generic arguments for an item that is defined can be reported
+//! right away, whenever the link or the item is expanded second.
+
+use crate::cache::{self, UnresolvedLink};
+
+/// Items whose names are at most this far from a missing name may be suggested in its place,
+/// unless the names are very short.
+const MAX_SUGGESTION_DISTANCE: usize = 3;
+
+/// Generates an error for every link that has not been fulfilled.
+pub fn check_links() -> proc_macro2::TokenStream {
+    let defined = cache::defined_items();
+    cache::unresolved_links()
+        .iter()
+        .map(|link| {
+            let message = unresolved_link_message(link, &defined);
+            syn::Error::new(proc_macro2::Span::call_site(), message).to_compile_error()
+        })
+        .collect()
+}
+
+/// Returns an error if the item that a link refers to has been defined, but only with a different
+/// number of generic arguments than the link was given.
+pub fn check_link_arity(path: &syn::Path, num_generics: usize, is_trait: bool) -> Option<syn::Error> {
+    let arities = cache::mismatched_arities(path, num_generics, is_trait);
+    if arities.is_empty() {
+        return None;
+    }
+    let defined = arities
+        .iter()
+        .map(|num_generics| describe_generics(*num_generics))
+        .collect::<Vec<_>>()
+        .join(" or ");
+    let message = format!(
+        "`{}` is defined with {}, but is linked here with {}",
+        path.segments.last().unwrap().ident,
+        defined,
+        describe_generics(num_generics),
+    );
+    Some(syn::Error::new_spanned(path, message))
+}
+
+/// Generates an error for every earlier link to a newly defined item that was given a different
+/// number of generic arguments than the item was defined with.
+pub fn check_definition_arity(
+    ident: &syn::Ident,
+    num_generics: usize,
+    module: Option<&syn::Path>,
+    is_trait: bool,
+) -> proc_macro2::TokenStream {
+    cache::take_mismatched_links(ident, num_generics, module, is_trait)
+        .iter()
+        .map(|link| {
+            let message = format!(
+                "`{}` is linked to `{}` with {}, but `{}` is defined with {}",
+                link.linked_from,
+                link.name,
+                describe_generics(link.num_generics),
+                link.name,
+                describe_generics(num_generics),
+            );
+            syn::Error::new(ident.span(), message).to_compile_error()
+        })
+        .collect()
+}
+
+/// Describes an unresolved link, suggesting a defined item that may have been intended.
+fn unresolved_link_message(link: &UnresolvedLink, defined: &[(String, usize)]) -> String {
+    let mut message = format!(
+        "`{}` is linked to `{}` with {}, but no such trait or enum was defined with #[enum_dispatch]",
+        link.linked_from,
+        link.name,
+        describe_generics(link.num_generics),
+    );
+    if let Some((name, num_generics)) = suggestion(link, defined) {
+        if *name == link.name {
+            message += &format!(
+                ". Note that `{}` was defined with {}",
+                name,
+                describe_generics(*num_generics)
+            );
+        } else {
+            message += &format!(". Did you mean `{}`?", name);
+        }
+    }
+    // A path like `other_crate::Trait` is relative to the current crate, so it can never refer to a
+    // trait exported from another crate.
+    let segments = &link.path.segments;
+    if link.path.leading_colon.is_none()
+        && segments.len() >= 2
+        && !["crate", "self", "super"].iter().any(|keyword| segments[0].ident == keyword)
+    {
+        let path = segments
+            .iter()
+            .map(|segment| segment.ident.to_string())
+            .collect::<Vec<_>>()
+            .join("::");
+        message += &format!(
+            ". To link to a trait exported from another crate, use a leading `::`, like `::{}`",
+            path
+        );
+    }
+    message
+}
+
+/// Returns a human readable description of a number of generic arguments.
+fn describe_generics(num_generics: usize) -> String {
+    match num_generics {
+        1 => "1 generic argument".to_string(),
+        n => format!("{} generic arguments", n),
+    }
+}
+
+/// Finds the defined item most likely to have been intended by an unresolved link. An item with
+/// the same name but a different number of generic arguments is preferred, followed by the item
+/// with the most similar name, other than the item that the link came from.
+fn suggestion<'a>(link: &UnresolvedLink, defined: &'a [(String, usize)]) -> Option<&'a (String, usize)> {
+    if let Some(same_name) = defined.iter().find(|(name, _)| *name == link.name) {
+        return Some(same_name);
+    }
+    let max_distance = MAX_SUGGESTION_DISTANCE.min(link.name.chars().count() / 3).max(1);
+    defined
+        .iter()
+        .filter(|(name, _)| *name != link.linked_from)
+        .map(|item| (edit_distance(&item.0, &link.name), item))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, item)| item)
+}
+
+/// Computes the optimal string alignment distance between two strings, ignoring case differences.
+/// This is the Levenshtein distance, except that swapping two adjacent characters counts as a
+/// single edit, so that a typo like `Shpae` is as close to `Shape` as a single wrong character.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.to_lowercase().chars().collect();
+    let b: Vec<char> = b.to_lowercase().chars().collect();
+    let mut before_previous: Vec<usize> = vec![];
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != b_char);
+            let mut distance = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+            if i > 0 && j > 0 && *a_char == b[j - 1] && a[i - 1] == *b_char {
+                distance = distance.min(before_previous[j - 1] + 1);
+            }
+            current.push(distance);
+        }
+        before_previous = std::mem::replace(&mut previous, current);
+    }
+    previous[b.len()]
+}
@@ -0,0 +1,80 @@
+//! Provides utilities for linking enums to traits defined in other crates.
+//!
+//! The cache only lives as long as a single compilation, so it can't be used to share trait
+//! definitions between crates. Instead, traits annotated with `#[enum_dispatch(export)]` carry
+//! their own definition in a hidden, exported `macro_rules!` macro. An enum in another crate that
+//! links to the trait with an extern path (e.g. `#[enum_dispatch(::core_traits::Trait)]`) invokes
+//! that macro, passing along its own definition and a callback. The exported macro then invokes
+//! the callback with both definitions, which is enough to generate the impl.
+
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+
+use crate::enum_dispatch_item::EnumDispatchItem;
+
+/// Returns the name of the macro used to export the definition of the given trait.
+fn export_macro_name(trait_name: &syn::Ident) -> syn::Ident {
+    quote::format_ident!("__enum_dispatch_export_{}", trait_name)
+}
+
+/// Generates an exported macro carrying the definition of the given trait. When invoked as
+/// `macro!(callback, args...)`, it expands to `callback! { args... { trait definition } }`.
+pub fn generate_export_macro(traitdef: &syn::ItemTrait) -> TokenStream {
+    let macro_name = export_macro_name(&traitdef.ident);
+    quote! {
+        #[doc(hidden)]
+        #[macro_export]
+        macro_rules! #macro_name {
+            ($callback:path, $($args:tt)*) => {
+                $callback! { $($args)* { #traitdef } }
+            };
+        }
+    }
+}
+
+/// Generates an invocation of the exported macro of the trait at `trait_path` in another crate,
+/// which will call back into `__enum_dispatch_link` to implement the trait for the given enum.
+pub fn generate_link_request(trait_path: &syn::Path, enumdef: &EnumDispatchItem) -> TokenStream {
+    let mut trait_path = trait_path.clone();
+    let crate_name = trait_path.segments[0].ident.clone();
+    let last = trait_path.segments.last_mut().unwrap();
+    let macro_name = export_macro_name(&last.ident);
+    // Generic arguments are kept, to be substituted for the trait's generic parameters.
+    quote! {
+        ::#crate_name::#macro_name!(::enum_dispatch::__enum_dispatch_link, (#trait_path) { #enumdef });
+    }
+}
+
+/// The arguments passed to `__enum_dispatch_link` by an exported macro.
+pub struct LinkCallback {
+    pub trait_path: syn::Path,
+    pub enumdef: EnumDispatchItem,
+    pub traitdef: syn::ItemTrait,
+}
+
+impl syn::parse::Parse for LinkCallback {
+    fn parse(input: syn::parse::ParseStream) -> syn::parse::Result<Self> {
+        let content;
+        syn::parenthesized!(content in input);
+        let trait_path = content.parse()?;
+        let content;
+        syn::braced!(content in input);
+        let enumdef = respan(content.parse::<TokenStream>()?)?;
+        let content;
+        syn::braced!(content in input);
+        let traitdef = respan(content.parse::<TokenStream>()?)?;
+        Ok(Self {
+            trait_path,
+            enumdef,
+            traitdef,
+        })
+    }
+}
+
+/// Tokens passed through a `macro_rules!` macro are subject to its hygiene, so identifiers like
+/// `self` and argument names from the trait definition would not match the identifiers generated
+/// in the impl. Parsing the tokens from a string gives them all the same call-site span, just
+/// like definitions retrieved from the cache.
+fn respan<T: syn::parse::Parse>(tokens: TokenStream) -> syn::parse::Result<T> {
+    syn::parse_str(&tokens.into_token_stream().to_string())
+}
@@ -2,7 +2,7 @@
 //! list of arguments that can be passed to an `#[enum_dispatch(...)]` attribute.
 
 pub struct EnumDispatchArgList {
-    pub arg_list: syn::punctuated::Punctuated<syn::Path, syn::token::Comma>,
+    pub arg_list: syn::punctuated::Punctuated<EnumDispatchArg, syn::token::Comma>,
 }
 
 impl syn::parse::Parse for EnumDispatchArgList {
@@ -11,3 +11,162 @@ impl syn::parse::Parse for EnumDispatchArgList {
         Ok(Self { arg_list })
     }
 }
+
+/// A single argument to an `#[enum_dispatch(...)]` attribute.
+#[derive(Clone)]
+pub enum EnumDispatchArg {
+    /// The name of a trait or enum to link with, e.g. `KnobControl` or `Foo<T>`.
+    Link(syn::Path),
+    /// A value for an associated constant of any linked trait, e.g. `NAME = "knob"`.
+    ConstValue(syn::Ident, syn::Expr),
+    /// A type for an associated type of any linked trait, e.g. `type Output = u32`.
+    AssocType(syn::Ident, syn::Type),
+    /// Generate inherent methods on the enum returning the value of each associated constant of
+    /// linked traits for the active variant, i.e. `const_accessors`.
+    ConstAccessors(syn::Ident),
+    /// Generate a fieldless `Kind` enum with one variant for each variant of the enum, i.e.
+    /// `kind`.
+    Kind(syn::Ident),
+    /// The variant whose type implements static trait methods for the enum, e.g.
+    /// `static_default = LinearKnob`.
+    StaticDefault(syn::Ident, syn::Ident),
+    /// Export the annotated trait for use by enums in other crates, i.e. `export`.
+    Export(syn::Ident),
+    /// The absolute path of the module that the annotated item is defined in, e.g.
+    /// `module = crate::engine::ops`.
+    Module(syn::Ident, syn::Path),
+    /// What to do when a method taking other `Self` arguments is called with mismatched variants,
+    /// optionally for a single method. Either a panic message or a function to call, e.g.
+    /// `mismatch = "incompatible shapes"` or `mismatch(distance) = crate::shapes::far_apart`.
+    Mismatch(syn::Ident, Option<syn::Ident>, syn::Expr),
+    /// Generate an error type holding the original value for the generated `TryFrom` impls,
+    /// instead of using `&'static str`, i.e. `try_from_error`.
+    TryFromError(syn::Ident),
+    /// Don't generate `From` impls for any variant of the enum, i.e. `no_from`.
+    NoFrom(syn::Ident),
+    /// Don't generate `TryFrom` impls for any variant of the enum, i.e. `no_try_from`.
+    NoTryFrom(syn::Ident),
+    /// Generate `as_*`, `as_*_mut` and `is_*` methods for each variant of the enum, along with
+    /// generic `variant::<T>()`-style methods, i.e. `accessors`.
+    Accessors(syn::Ident),
+    /// Where predicates for generated trait impls, replacing the bounds inferred for generic
+    /// variant types, e.g. `bound(T: Display + Send)`. `bound()` turns off inferred bounds.
+    Bound(syn::Ident, syn::punctuated::Punctuated<syn::WherePredicate, syn::token::Comma>),
+}
+
+impl syn::parse::Parse for EnumDispatchArg {
+    fn parse(input: &syn::parse::ParseBuffer) -> Result<Self, syn::Error> {
+        if input.peek(syn::Token![type]) {
+            input.parse::<syn::Token![type]>()?;
+            let ident = input.parse()?;
+            input.parse::<syn::Token![=]>()?;
+            let ty = input.parse()?;
+            Ok(Self::AssocType(ident, ty))
+        } else if input.peek(syn::Ident) && input.peek2(syn::token::Paren) && starts_with(input, "bound") {
+            let keyword = input.parse()?;
+            let content;
+            syn::parenthesized!(content in input);
+            Ok(Self::Bound(keyword, content.parse_terminated(syn::WherePredicate::parse, syn::Token![,])?))
+        } else if input.peek(syn::Ident) && input.peek2(syn::token::Paren) && starts_with(input, "mismatch") {
+            let keyword = input.parse()?;
+            let content;
+            syn::parenthesized!(content in input);
+            let method = content.parse()?;
+            input.parse::<syn::Token![=]>()?;
+            Ok(Self::Mismatch(keyword, Some(method), input.parse()?))
+        } else if input.peek(syn::Ident) && input.peek2(syn::Token![=]) {
+            let ident: syn::Ident = input.parse()?;
+            input.parse::<syn::Token![=]>()?;
+            if ident == "static_default" {
+                return Ok(Self::StaticDefault(ident, input.parse()?));
+            }
+            if ident == "module" {
+                return Ok(Self::Module(ident, input.parse()?));
+            }
+            if ident == "mismatch" {
+                return Ok(Self::Mismatch(ident, None, input.parse()?));
+            }
+            let value = input.parse()?;
+            Ok(Self::ConstValue(ident, value))
+        } else if is_keyword(input, "const_accessors") {
+            Ok(Self::ConstAccessors(input.parse()?))
+        } else if is_keyword(input, "kind") {
+            Ok(Self::Kind(input.parse()?))
+        } else if is_keyword(input, "export") {
+            Ok(Self::Export(input.parse()?))
+        } else if is_keyword(input, "try_from_error") {
+            Ok(Self::TryFromError(input.parse()?))
+        } else if is_keyword(input, "no_from") {
+            Ok(Self::NoFrom(input.parse()?))
+        } else if is_keyword(input, "no_try_from") {
+            Ok(Self::NoTryFrom(input.parse()?))
+        } else if is_keyword(input, "accessors") {
+            Ok(Self::Accessors(input.parse()?))
+        } else {
+            Ok(Self::Link(input.parse()?))
+        }
+    }
+}
+
+/// Returns true if the next argument in the input begins with the given keyword.
+fn starts_with(input: &syn::parse::ParseBuffer, keyword: &str) -> bool {
+    input.fork().parse::<syn::Ident>().is_ok_and(|ident| ident == keyword)
+}
+
+/// Returns true if the next argument in the input is the given standalone keyword, rather than
+/// the beginning of a path.
+fn is_keyword(input: &syn::parse::ParseBuffer, keyword: &str) -> bool {
+    let fork = input.fork();
+    match fork.parse::<syn::Ident>() {
+        Ok(ident) => ident == keyword && (fork.is_empty() || fork.peek(syn::Token![,])),
+        Err(_) => false,
+    }
+}
+
+impl quote::ToTokens for EnumDispatchArg {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        match self {
+            Self::Link(path) => path.to_tokens(tokens),
+            Self::ConstValue(ident, value) => {
+                ident.to_tokens(tokens);
+                syn::token::Eq::default().to_tokens(tokens);
+                value.to_tokens(tokens);
+            }
+            Self::AssocType(ident, ty) => {
+                syn::token::Type::default().to_tokens(tokens);
+                ident.to_tokens(tokens);
+                syn::token::Eq::default().to_tokens(tokens);
+                ty.to_tokens(tokens);
+            }
+            Self::ConstAccessors(keyword)
+            | Self::Kind(keyword)
+            | Self::Export(keyword)
+            | Self::TryFromError(keyword)
+            | Self::NoFrom(keyword)
+            | Self::NoTryFrom(keyword)
+            | Self::Accessors(keyword) => keyword.to_tokens(tokens),
+            Self::StaticDefault(keyword, variant) => {
+                keyword.to_tokens(tokens);
+                syn::token::Eq::default().to_tokens(tokens);
+                variant.to_tokens(tokens);
+            }
+            Self::Module(keyword, module) => {
+                keyword.to_tokens(tokens);
+                syn::token::Eq::default().to_tokens(tokens);
+                module.to_tokens(tokens);
+            }
+            Self::Mismatch(keyword, method, fallback) => {
+                keyword.to_tokens(tokens);
+                if let Some(method) = method {
+                    syn::token::Paren::default().surround(tokens, |tokens| method.to_tokens(tokens));
+                }
+                syn::token::Eq::default().to_tokens(tokens);
+                fallback.to_tokens(tokens);
+            }
+            Self::Bound(keyword, predicates) => {
+                keyword.to_tokens(tokens);
+                syn::token::Paren::default().surround(tokens, |tokens| predicates.to_tokens(tokens));
+            }
+        }
+    }
+}
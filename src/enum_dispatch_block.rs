@@ -0,0 +1,78 @@
+//! Provides an implementation of a `syn`- and `quote`-compatible syntax item describing the
+//! contents of an `enum_dispatch_block!` invocation.
+//!
+//! A block contains any number of trait definitions and `enum_dispatch` enum definitions, in any
+//! order, optionally separated by semicolons. Every enum in the block implements every trait in
+//! the block.
+//!
+//! Since all definitions are available within a single invocation, nothing needs to be stored in
+//! the cache. Non-link arguments for an enum (such as `kind` or associated item values) can still
+//! be passed through an `#[enum_dispatch(...)]` attribute on the enum inside the block.
+
+use crate::enum_dispatch_arg_list::{EnumDispatchArg, EnumDispatchArgList};
+use crate::enum_dispatch_item::EnumDispatchItem;
+
+/// A structure that can be used to store syntax information about an `enum_dispatch_block!`
+/// invocation.
+pub struct EnumDispatchBlock {
+    pub traits: Vec<syn::ItemTrait>,
+    pub enums: Vec<EnumDispatchItem>,
+}
+
+/// Allows `EnumDispatchBlock`s to be parsed from `String`s or `TokenStream`s.
+impl syn::parse::Parse for EnumDispatchBlock {
+    fn parse(input: syn::parse::ParseStream) -> syn::parse::Result<Self> {
+        let mut traits = vec![];
+        let mut enums = vec![];
+        while !input.is_empty() {
+            if input.peek(syn::Token![;]) {
+                input.parse::<syn::Token![;]>()?;
+                continue;
+            }
+            // Look past any attributes and visibility to find out which kind of item is next.
+            let fork = input.fork();
+            fork.call(syn::Attribute::parse_outer)?;
+            fork.parse::<syn::Visibility>()?;
+            if fork.peek(syn::Token![enum]) {
+                let mut enumdef: EnumDispatchItem = input.parse()?;
+                enumdef.options = take_options(&mut enumdef.attrs)?;
+                enums.push(enumdef);
+            } else {
+                let mut traitdef: syn::ItemTrait = input.parse()?;
+                for option in take_options(&mut traitdef.attrs)? {
+                    if !matches!(option, EnumDispatchArg::Module(..)) {
+                        return Err(syn::Error::new_spanned(
+                            option,
+                            "This argument to #[enum_dispatch(...)] can only be used on enums",
+                        ));
+                    }
+                }
+                traits.push(traitdef);
+            }
+        }
+        Ok(Self { traits, enums })
+    }
+}
+
+/// Removes any `#[enum_dispatch(...)]` attributes from an item in the block, returning the
+/// arguments they contained. Links to other items are not allowed, since every enum in the block
+/// is already linked to every trait in the block.
+fn take_options(attrs: &mut Vec<syn::Attribute>) -> syn::parse::Result<Vec<EnumDispatchArg>> {
+    let mut options = vec![];
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("enum_dispatch")) {
+        if let syn::Meta::Path(_) = attr.meta {
+            continue;
+        }
+        for arg in attr.parse_args::<EnumDispatchArgList>()?.arg_list {
+            if let EnumDispatchArg::Link(path) = arg {
+                return Err(syn::Error::new_spanned(
+                    path,
+                    "Items in an `enum_dispatch_block!` are linked automatically",
+                ));
+            }
+            options.push(arg);
+        }
+    }
+    attrs.retain(|attr| !attr.path().is_ident("enum_dispatch"));
+    Ok(options)
+}
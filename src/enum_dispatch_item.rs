@@ -7,9 +7,16 @@
 //! after existing scoped types, a normal Rust enum can be parsed as an EnumDispatchItem without
 //! issue.
 use quote::TokenStreamExt;
+use std::convert::TryFrom;
 
+use crate::enum_dispatch_arg_list::EnumDispatchArg;
 use crate::enum_dispatch_variant::EnumDispatchVariant;
 use crate::filter_attrs::FilterAttrs;
+use crate::syn_utils::combine_errors;
+
+/// Name of the attribute used to carry `options` through the cache. It is never emitted in the
+/// expanded enum definition.
+const OPTIONS_ATTR: &str = "enum_dispatch_options";
 
 /// A structure that can be used to store syntax information about an `enum_dispatch` enum.
 ///
@@ -23,12 +30,20 @@ pub struct EnumDispatchItem {
     pub generics: syn::Generics,
     brace_token: syn::token::Brace,
     pub variants: syn::punctuated::Punctuated<EnumDispatchVariant, syn::token::Comma>,
+    /// Any non-link arguments passed to the `#[enum_dispatch(...)]` attribute on this enum.
+    pub options: Vec<EnumDispatchArg>,
 }
 
 /// Allows `EnumDispatchItem`s to be parsed from `String`s or `TokenStream`s.
 impl syn::parse::Parse for EnumDispatchItem {
     fn parse(input: syn::parse::ParseStream) -> syn::parse::Result<Self> {
-        let attrs = input.call(syn::Attribute::parse_outer)?;
+        let mut attrs = input.call(syn::Attribute::parse_outer)?;
+        let mut options = vec![];
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident(OPTIONS_ATTR)) {
+            let args = attr.parse_args::<crate::enum_dispatch_arg_list::EnumDispatchArgList>()?;
+            options.extend(args.arg_list);
+        }
+        attrs.retain(|attr| !attr.path().is_ident(OPTIONS_ATTR));
         let vis: syn::Visibility = input.parse()?;
         let enum_token = input.parse::<syn::Token![enum]>()?;
         let ident: syn::Ident = input.parse()?;
@@ -36,7 +51,7 @@ impl syn::parse::Parse for EnumDispatchItem {
         let where_clause = input.parse()?;
         let content;
         let brace_token = syn::braced!(content in input);
-        let variants = content.parse_terminated(EnumDispatchVariant::parse, syn::Token![,])?;
+        let variants = collect_variants(content.parse_terminated(syn::Variant::parse, syn::Token![,])?)?;
         Ok(Self {
             attrs,
             vis,
@@ -48,14 +63,57 @@ impl syn::parse::Parse for EnumDispatchItem {
             },
             brace_token,
             variants,
+            options,
         })
     }
 }
 
+/// Converts standard enum variants into `enum_dispatch` variants, combining the errors for every
+/// invalid variant. At most one variant can be dynamic.
+fn collect_variants(
+    variants: syn::punctuated::Punctuated<syn::Variant, syn::token::Comma>,
+) -> syn::parse::Result<syn::punctuated::Punctuated<EnumDispatchVariant, syn::token::Comma>> {
+    let mut errors = vec![];
+    let mut converted = syn::punctuated::Punctuated::new();
+    for pair in variants.into_pairs() {
+        let (variant, comma) = pair.into_tuple();
+        match EnumDispatchVariant::try_from(variant) {
+            Ok(variant) => {
+                converted.push_value(variant);
+                if let Some(comma) = comma {
+                    converted.push_punct(comma);
+                }
+            }
+            Err(e) => errors.push(e),
+        }
+    }
+    errors.extend(
+        converted
+            .iter()
+            .filter(|variant: &&EnumDispatchVariant| variant.dynamic)
+            .skip(1)
+            .map(|variant| {
+                syn::Error::new_spanned(
+                    &variant.ident,
+                    "Only one enum_dispatch variant can be marked with #[enum_dispatch(dynamic)]",
+                )
+            }),
+    );
+    match combine_errors(errors) {
+        Some(errors) => Err(errors),
+        None => Ok(converted),
+    }
+}
+
 /// Allows `EnumDispatchItem`s to be converted into `TokenStream`s.
 impl quote::ToTokens for EnumDispatchItem {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         tokens.append_all(self.attrs.outer());
+        if !self.options.is_empty() {
+            let options_attr = syn::Ident::new(OPTIONS_ATTR, proc_macro2::Span::call_site());
+            let options = &self.options;
+            tokens.append_all(quote::quote! { #[#options_attr(#(#options),*)] });
+        }
         self.vis.to_tokens(tokens);
         self.enum_token.to_tokens(tokens);
         self.ident.to_tokens(tokens);
@@ -78,21 +136,7 @@ impl ::std::convert::From<EnumDispatchItem> for syn::ItemEnum {
             .map(|variant: &EnumDispatchVariant| syn::Variant {
                 attrs: variant.attrs.to_owned(),
                 ident: variant.ident.to_owned(),
-                fields: syn::Fields::Unnamed(syn::FieldsUnnamed {
-                    paren_token: Default::default(),
-                    unnamed: {
-                        let mut punctuated = syn::punctuated::Punctuated::new();
-                        punctuated.push(syn::Field {
-                            attrs: variant.field_attrs.to_owned(),
-                            vis: syn::Visibility::Inherited,
-                            ident: None,
-                            colon_token: Default::default(),
-                            ty: variant.ty.to_owned(),
-                            mutability: syn::FieldMutability::None,
-                        });
-                        punctuated
-                    },
-                }),
+                fields: variant.fields.to_owned(),
                 discriminant: None,
             })
             .collect();
@@ -4,60 +4,335 @@
 //! Each variant can be either just a type, or a name with a single associated tuple type
 //! parameter. In the first form, the name is simply the same as the type. In the second, the name
 //! is explicitly specified.
+//!
+//! Variants can also have multiple fields, either unnamed or named, as long as the field holding
+//! the value that trait methods are dispatched to is marked with `#[dispatch]`, e.g.
+//! `Circle(#[dispatch] Circle, Metadata)` or `Circle { #[dispatch] shape: Circle, id: u32 }`. A
+//! variant with a single named field dispatches to that field without any marker.
+//!
+//! Variants can also be annotated with `#[enum_dispatch(...)]` to customize the code generated for
+//! them, e.g. `#[enum_dispatch(skip_from)]`. A bare name annotated with `#[enum_dispatch(unit)]` is
+//! kept as a unit variant without any value to dispatch to, rather than being treated as a type.
+//! A variant annotated with `#[enum_dispatch(dynamic)]` holds a trait object, e.g.
+//! `Other(Box<dyn Trait>)`, which trait methods are dispatched to through its vtable.
 
+use std::convert::TryFrom;
 use std::iter::FromIterator;
 
-use quote::TokenStreamExt;
+use quote::{quote, ToTokens, TokenStreamExt};
 
 use crate::filter_attrs::FilterAttrs;
 
+/// Name of the attribute marking the field to dispatch to in variants with multiple fields.
+const DISPATCH_ATTR: &str = "dispatch";
+
 /// A structure that can be used to store syntax information about an `enum_dispatch` enum variant.
 #[derive(Clone)]
 pub struct EnumDispatchVariant {
     pub attrs: Vec<syn::Attribute>,
     pub ident: syn::Ident,
-    pub field_attrs: Vec<syn::Attribute>,
+    /// All fields of the variant, without any `#[dispatch]` markers.
+    pub fields: syn::Fields,
+    /// The field that trait methods are dispatched to.
+    pub dispatch_field: syn::Member,
+    /// The type of the field that trait methods are dispatched to.
     pub ty: syn::Type,
+    /// Whether conversion impls between the enum and this variant's type should be skipped, e.g.
+    /// because another variant has the same type. Always set for unit variants.
+    pub skip_from: bool,
+    /// Whether this is a unit variant, which has no fields. In that case, `dispatch_field` and `ty`
+    /// are placeholders that must not be used in generated code.
+    pub unit: bool,
+    /// Functions to call instead of trait methods for a unit variant, by method name, e.g.
+    /// `fallback(area) = zero_area`.
+    pub fallbacks: Vec<(syn::Ident, syn::Expr)>,
+    /// Whether this variant holds a trait object, like `Box<dyn Trait>` or `Arc<dyn Trait>`,
+    /// rather than a type implementing the trait itself.
+    pub dynamic: bool,
+    /// Whether a helper converting the trait object held by this dynamic variant into a built-in
+    /// variant should be generated. Requires the trait object to be held in a `Box`.
+    pub downcast: bool,
+}
+
+impl EnumDispatchVariant {
+    /// Returns whether the variant has a single unnamed field, i.e. it looks like `Name(Type)`.
+    fn is_newtype(&self) -> bool {
+        matches!(&self.fields, syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1)
+    }
+
+    /// Returns the fallback to call instead of the given trait method, if this is a unit variant
+    /// with a fallback for it.
+    pub fn fallback(&self, method: &syn::Ident) -> Option<&syn::Expr> {
+        self.fallbacks
+            .iter()
+            .rev()
+            .find(|(fallback_method, _)| fallback_method == method)
+            .map(|(_, fallback)| fallback)
+    }
+
+    /// Returns a pattern matching this variant, to be prefixed with the path to the enum. The
+    /// dispatch field is bound to the given name, or ignored if there is none. Any other fields are
+    /// always ignored.
+    pub fn pattern(&self, binding: Option<&syn::Ident>) -> proc_macro2::TokenStream {
+        let ident = &self.ident;
+        if self.unit {
+            return quote! { #ident };
+        }
+        let binding = match binding {
+            Some(binding) => binding.to_token_stream(),
+            None => quote! { _ },
+        };
+        if self.is_newtype() {
+            quote! { #ident(#binding) }
+        } else {
+            let dispatch_field = &self.dispatch_field;
+            quote! { #ident { #dispatch_field: #binding, .. } }
+        }
+    }
+
+    /// Returns an expression constructing this variant from the given value of its dispatch
+    /// field, to be prefixed with the path to the enum. Any other fields are set to their
+    /// `Default` values.
+    pub fn construct(&self, value: &syn::Expr) -> proc_macro2::TokenStream {
+        let ident = &self.ident;
+        if self.unit {
+            return quote! { #ident };
+        }
+        if self.is_newtype() {
+            return quote! { #ident(#value) };
+        }
+        let dispatch_field = &self.dispatch_field;
+        let other_fields = self
+            .fields
+            .members()
+            .filter(|member| member != dispatch_field);
+        quote! {
+            #ident {
+                #dispatch_field: #value,
+                #(#other_fields: ::core::default::Default::default(),)*
+            }
+        }
+    }
+    /// Returns true if this is a dynamic variant holding its trait object in a `Box`, which allows
+    /// the trait object to be borrowed mutably. Shared pointers like `Arc` only allow shared
+    /// borrows.
+    pub fn owns_trait_object(&self) -> bool {
+        self.dynamic && is_box(&self.ty)
+    }
 }
 
-/// Allows `EnumDispatchItem`s to be parsed from `String`s or `TokenStream`s.
+/// Allows `EnumDispatchVariant`s to be parsed from `String`s or `TokenStream`s.
 impl syn::parse::Parse for EnumDispatchVariant {
     fn parse(input: syn::parse::ParseStream) -> syn::parse::Result<Self> {
-        let attrs = input.call(syn::Attribute::parse_outer)?;
-        let ident: syn::Ident = input.parse()?;
-        let (field_attrs, ty) = if input.peek(syn::token::Brace) {
-            unimplemented!("enum_dispatch variants cannot have braces for arguments");
-        } else if input.peek(syn::token::Paren) {
-            let input: syn::FieldsUnnamed = input.parse()?;
-            let mut fields = input.unnamed.iter();
-            let field_1 = fields
-                .next()
-                .expect("Named enum_dispatch variants must have one unnamed field");
-            if fields.next().is_some() {
-                panic!("Named enum_dispatch variants can only have one unnamed field");
+        Self::try_from(input.parse::<syn::Variant>()?)
+    }
+}
+
+/// Any `enum_dispatch` variant is also a syntactically valid standard enum variant. Parsing a
+/// standard variant first allows an enum to be parsed in full even when some of its variants are
+/// invalid, so that errors for all of them can be reported at once.
+impl TryFrom<syn::Variant> for EnumDispatchVariant {
+    type Error = syn::Error;
+
+    fn try_from(mut variant: syn::Variant) -> syn::parse::Result<Self> {
+        let mut skip_from = false;
+        let mut unit = false;
+        let mut fallbacks = vec![];
+        let mut dynamic = false;
+        let mut downcast = false;
+        for attr in variant.attrs.iter().filter(|attr| attr.path().is_ident("enum_dispatch")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("skip_from") {
+                    skip_from = true;
+                    Ok(())
+                } else if meta.path.is_ident("unit") {
+                    unit = true;
+                    Ok(())
+                } else if meta.path.is_ident("dynamic") {
+                    dynamic = true;
+                    if meta.input.peek(syn::token::Paren) {
+                        meta.parse_nested_meta(|meta| {
+                            if meta.path.is_ident("downcast") {
+                                downcast = true;
+                                Ok(())
+                            } else {
+                                Err(meta.error("Unsupported dynamic variant argument. Expected `downcast`"))
+                            }
+                        })?;
+                    }
+                    Ok(())
+                } else if meta.path.is_ident("fallback") {
+                    let content;
+                    syn::parenthesized!(content in meta.input);
+                    let method = content.parse()?;
+                    let fallback = meta.value()?.parse()?;
+                    fallbacks.push((method, fallback));
+                    Ok(())
+                } else {
+                    Err(meta.error(
+                        "Unsupported enum_dispatch variant argument. Expected `skip_from`, `unit`, `dynamic` or `fallback(method) = ...`",
+                    ))
+                }
+            })?;
+        }
+        variant.attrs.retain(|attr| !attr.path().is_ident("enum_dispatch"));
+        if let Some((_, discriminant)) = &variant.discriminant {
+            return Err(syn::Error::new_spanned(
+                discriminant,
+                "enum_dispatch variants cannot have explicit discriminants",
+            ));
+        }
+        if unit {
+            if dynamic {
+                return Err(syn::Error::new_spanned(
+                    variant.ident,
+                    "Unit enum_dispatch variants cannot be dynamic",
+                ));
             }
-            (field_1.attrs.clone(), field_1.ty.clone())
-        } else {
-            (vec![], into_type(ident.clone()))
+            if !matches!(variant.fields, syn::Fields::Unit) {
+                return Err(syn::Error::new_spanned(
+                    variant.fields,
+                    "Unit enum_dispatch variants cannot have fields",
+                ));
+            }
+            return Ok(EnumDispatchVariant {
+                attrs: variant.attrs,
+                ident: variant.ident,
+                fields: syn::Fields::Unit,
+                dispatch_field: syn::Member::Unnamed(syn::Index::from(0)),
+                ty: syn::parse_quote! { () },
+                skip_from: true,
+                unit,
+                fallbacks,
+                dynamic,
+                downcast,
+            });
+        }
+        if let Some((method, _)) = fallbacks.first() {
+            return Err(syn::Error::new_spanned(
+                method,
+                "Fallbacks can only be used on unit variants, marked with #[enum_dispatch(unit)]",
+            ));
+        }
+        let mut fields = match variant.fields {
+            syn::Fields::Unit => syn::Fields::Unnamed(syn::FieldsUnnamed {
+                paren_token: Default::default(),
+                unnamed: syn::punctuated::Punctuated::from_iter(vec![syn::Field {
+                    attrs: vec![],
+                    vis: syn::Visibility::Inherited,
+                    mutability: syn::FieldMutability::None,
+                    ident: None,
+                    colon_token: None,
+                    ty: into_type(variant.ident.clone()),
+                }]),
+            }),
+            fields => fields,
+        };
+        let (index, field) = find_dispatch_field(&mut fields)?;
+        let dispatch_field = match &field.ident {
+            Some(ident) => syn::Member::Named(ident.clone()),
+            None => syn::Member::Unnamed(syn::Index::from(index)),
         };
+        let ty = field.ty.clone();
+        if downcast && !is_box(&ty) {
+            return Err(syn::Error::new_spanned(
+                ty,
+                "Only dynamic variants holding a `Box` can be downcast",
+            ));
+        }
         Ok(EnumDispatchVariant {
-            attrs,
-            ident,
-            field_attrs,
+            attrs: variant.attrs,
+            ident: variant.ident,
+            fields,
+            dispatch_field,
             ty,
+            skip_from,
+            unit,
+            fallbacks,
+            dynamic,
+            downcast,
         })
     }
 }
 
+/// Finds the field that trait methods should be dispatched to, removing its `#[dispatch]` marker.
+/// A variant with a single field always dispatches to that field.
+fn find_dispatch_field(fields: &mut syn::Fields) -> syn::parse::Result<(usize, &syn::Field)> {
+    let mut marked = vec![];
+    for (i, field) in fields.iter_mut().enumerate() {
+        let len = field.attrs.len();
+        for attr in field.attrs.iter().filter(|attr| attr.path().is_ident(DISPATCH_ATTR)) {
+            attr.meta.require_path_only()?;
+        }
+        field.attrs.retain(|attr| !attr.path().is_ident(DISPATCH_ATTR));
+        if field.attrs.len() != len {
+            marked.push(i);
+        }
+    }
+    let index = match (marked.as_slice(), fields.len()) {
+        ([index], _) => *index,
+        ([], 1) => 0,
+        ([], _) => {
+            return Err(syn::Error::new_spanned(
+                &*fields,
+                "enum_dispatch variants with more than one field must mark the field to dispatch to with #[dispatch]",
+            ))
+        }
+        (_, _) => {
+            return Err(syn::Error::new_spanned(
+                &*fields,
+                "Only one field of an enum_dispatch variant can be marked with #[dispatch]",
+            ))
+        }
+    };
+    Ok((index, fields.iter().nth(index).unwrap()))
+}
+
 /// Allows `EnumDispatchVariant`s to be converted into `TokenStream`s.
 impl quote::ToTokens for EnumDispatchVariant {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         tokens.append_all(self.attrs.outer());
+        if self.unit {
+            let fallbacks = self
+                .fallbacks
+                .iter()
+                .map(|(method, fallback)| quote! { fallback(#method) = #fallback });
+            tokens.append_all(quote! { #[enum_dispatch(unit #(, #fallbacks)*)] });
+            self.ident.to_tokens(tokens);
+            return;
+        }
+        let skip_from = self.skip_from.then(|| quote! { skip_from });
+        let dynamic = match (self.dynamic, self.downcast) {
+            (true, true) => Some(quote! { dynamic(downcast) }),
+            (true, false) => Some(quote! { dynamic }),
+            (false, _) => None,
+        };
+        let args = skip_from.into_iter().chain(dynamic).collect::<Vec<_>>();
+        if !args.is_empty() {
+            tokens.append_all(quote! { #[enum_dispatch(#(#args),*)] });
+        }
         self.ident.to_tokens(tokens);
-        syn::token::Paren::default().surround(tokens, |tokens| {
-            tokens.append_all(self.field_attrs.iter());
-            self.ty.to_tokens(tokens);
-        });
+        let mut fields = self.fields.clone();
+        if fields.len() > 1 {
+            let dispatch_attr = syn::Ident::new(DISPATCH_ATTR, proc_macro2::Span::call_site());
+            let (field, _) = fields
+                .iter_mut()
+                .zip(self.fields.members())
+                .find(|(_, member)| *member == self.dispatch_field)
+                .unwrap();
+            field.attrs.insert(0, syn::parse_quote! { #[#dispatch_attr] });
+        }
+        fields.to_tokens(tokens);
+    }
+}
+
+/// Returns true if the given type is a `Box`, e.g. `Box<dyn Trait>`.
+fn is_box(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => {
+            path.segments.last().is_some_and(|segment| segment.ident == "Box")
+        }
+        _ => false,
     }
 }
 
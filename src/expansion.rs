@@ -4,9 +4,12 @@ use crate::cache;
 use quote::{quote, ToTokens};
 use syn::spanned::Spanned;
 
+use std::collections::HashMap;
+
+use crate::enum_dispatch_arg_list::EnumDispatchArg;
 use crate::enum_dispatch_item::EnumDispatchItem;
 use crate::enum_dispatch_variant::EnumDispatchVariant;
-use crate::syn_utils::plain_identifier_expr;
+use crate::syn_utils::{combine_errors, plain_identifier_expr, snake_case};
 
 /// Name bound to the single enum field in generated match statements. It doesn't really matter
 /// what this is, as long as it's consistent across the left and right sides of generated match
@@ -14,19 +17,456 @@ use crate::syn_utils::plain_identifier_expr;
 const FIELDNAME: &str = "inner";
 
 /// Implements the specified trait for the given enum definition, assuming the trait definition is
-/// already present in local storage.
+/// already present in local storage. Conversion impls for the enum are also generated the first
+/// time it is linked.
+///
+/// `enum_path` and `trait_path` are used to refer to the enum and trait in the generated code, so
+/// they must be valid at the location where the generated code will be emitted. Generic arguments
+/// on the trait path, as given in the link, are substituted for the trait's generic parameters, so
+/// that e.g. `Handler<Request>` is implemented for exactly that type. Generic arguments on the enum
+/// path only have their lifetimes substituted, since the others are just placeholders.
+///
+/// Associated type bindings on the trait path, like `Item = u8`, are used as the associated types
+/// of the generated impl, and bounds like `Item: Clone` require the associated types of all
+/// variant types to satisfy them.
 pub fn add_enum_impls(
     enum_def: EnumDispatchItem,
+    enum_path: syn::Path,
+    traitdef: syn::ItemTrait,
+    trait_path: syn::Path,
+) -> proc_macro2::TokenStream {
+    let (mut linked_enum_def, enum_path) = substitute_enum_lifetimes(enum_def.clone(), enum_path);
+    let mut trait_path = trait_path;
+    let associated_args = take_associated_arguments(&mut trait_path);
+    let (traitdef, trait_path) = match substitute_trait_arguments(traitdef, trait_path) {
+        Ok(substituted) => substituted,
+        Err(error) => return error.to_compile_error(),
+    };
+    apply_associated_arguments(&mut linked_enum_def, &trait_path, associated_args);
+    let pinned = has_pinned_receivers(&traitdef);
+
+    let module = enum_def.options.iter().rev().find_map(|option| match option {
+        EnumDispatchArg::Module(_, module) => Some(module),
+        _ => None,
+    });
+
+    let num_generics = enum_def.generics.type_params().count();
+    let mut const_accessors = cache::const_accessors_by_enum(&enum_def.ident, num_generics, module);
+    let mut impls = add_trait_impl(
+        &linked_enum_def,
+        &enum_path,
+        traitdef,
+        trait_path,
+        &mut const_accessors,
+    );
+    cache::cache_enum_const_accessors(enum_def.ident.clone(), num_generics, module, const_accessors);
+
+    // Only generate pin guards once per enum_def, and only if they are needed
+    if pinned
+        && !cache::pin_guards_def_by_enum(
+            &enum_def.ident,
+            enum_def.generics.type_params().count(),
+            module,
+        )
+    {
+        impls.extend(generate_pin_guards(&enum_def, &enum_path));
+        cache::cache_enum_pin_guards_defined(
+            enum_def.ident.clone(),
+            enum_def.generics.type_params().count(),
+            module,
+        );
+    }
+
+    // Only generate From impls once per enum_def
+    if !cache::conversion_impls_def_by_enum(
+        &enum_def.ident,
+        enum_def.generics.type_params().count(),
+        module,
+    ) {
+        impls.extend(add_conversion_impls(&enum_def, &enum_path));
+        cache::cache_enum_conversion_impls_defined(
+            enum_def.ident.clone(),
+            enum_def.generics.type_params().count(),
+            module,
+        );
+    }
+
+    impls
+}
+
+/// Removes associated type bindings and bounds, like `Item = u8` or `Item: Clone`, from the
+/// generic arguments of the final segment of a path to a trait, returning them.
+fn take_associated_arguments(trait_path: &mut syn::Path) -> Vec<syn::GenericArgument> {
+    let last = trait_path.segments.last_mut().unwrap();
+    let args = match &mut last.arguments {
+        syn::PathArguments::AngleBracketed(args) => args,
+        _ => return vec![],
+    };
+    let (associated, generic): (Vec<_>, Vec<_>) = std::mem::take(&mut args.args)
+        .into_iter()
+        .partition(|arg| {
+            matches!(arg, syn::GenericArgument::AssocType(_) | syn::GenericArgument::Constraint(_))
+        });
+    if generic.is_empty() {
+        last.arguments = syn::PathArguments::None;
+    } else {
+        args.args = generic.into_iter().collect();
+    }
+    associated
+}
+
+/// Applies associated type bindings and bounds given for a trait in a link to the enum definition
+/// used to implement it. Bindings are added as `#[enum_dispatch(type Name = ...)]` options, and
+/// bounds are added to the enum's where clause for the type of each variant.
+fn apply_associated_arguments(
+    enum_def: &mut EnumDispatchItem,
+    trait_path: &syn::Path,
+    associated_args: Vec<syn::GenericArgument>,
+) {
+    for arg in associated_args {
+        match arg {
+            syn::GenericArgument::AssocType(binding) => {
+                enum_def.options.push(EnumDispatchArg::AssocType(binding.ident, binding.ty));
+            }
+            syn::GenericArgument::Constraint(constraint) => {
+                let ident = &constraint.ident;
+                let bounds = &constraint.bounds;
+                // Unit and dynamic variants don't have a type implementing the trait.
+                let predicates = enum_def
+                    .variants
+                    .iter()
+                    .filter(|variant| !variant.unit && !variant.dynamic)
+                    .map(|variant| {
+                        let variant_type = &variant.ty;
+                        syn::parse_quote! { <#variant_type as #trait_path>::#ident: #bounds }
+                    })
+                    .collect::<Vec<syn::WherePredicate>>();
+                enum_def.generics.make_where_clause().predicates.extend(predicates);
+            }
+            _ => (),
+        }
+    }
+}
+
+/// What a generic parameter of a linked item is replaced with.
+enum GenericSubstitute {
+    Lifetime(syn::Lifetime),
+    Type(syn::Type),
+    Const(syn::Expr),
+}
+
+/// Substitutes the lifetime arguments given for an enum in a link, e.g. `'a` in
+/// `#[enum_dispatch(Frame<'a>)]`, for the lifetime parameters of its definition, in order. The
+/// definition is returned along with the path to the enum without any generic arguments.
+fn substitute_enum_lifetimes(
+    enum_def: EnumDispatchItem,
+    mut enum_path: syn::Path,
+) -> (EnumDispatchItem, syn::Path) {
+    let last = enum_path.segments.last_mut().unwrap();
+    let arguments = std::mem::replace(&mut last.arguments, syn::PathArguments::None);
+    let lifetimes = match arguments {
+        syn::PathArguments::AngleBracketed(args) => args
+            .args
+            .into_iter()
+            .filter_map(|arg| match arg {
+                syn::GenericArgument::Lifetime(lifetime) => Some(lifetime),
+                _ => None,
+            })
+            .collect(),
+        _ => vec![],
+    };
+    if lifetimes.is_empty() {
+        return (enum_def, enum_path);
+    }
+    let substitutions = enum_def
+        .generics
+        .lifetimes()
+        .map(|param| param.lifetime.ident.clone())
+        .zip(lifetimes.into_iter().map(GenericSubstitute::Lifetime))
+        .collect::<Vec<_>>();
+    let tokens = substitute_generics(enum_def.into_token_stream(), &substitutions);
+    (syn::parse2(tokens).unwrap(), enum_path)
+}
+
+/// Substitutes the generic arguments given for a trait in a link, e.g. `Request` in
+/// `#[enum_dispatch(Handler<Request>)]`, for the generic parameters of its definition. Lifetime
+/// arguments replace lifetime parameters in order, and other arguments replace type and const
+/// parameters in order, with `_` keeping the parameter's own name.
+///
+/// The returned definition has no generic parameters left. Instead, the arguments are moved to the
+/// returned path to the trait, as in `Handler::<Request>`. An error is returned if the numbers of
+/// lifetime and other arguments don't match the trait's parameters.
+fn substitute_trait_arguments(
+    mut traitdef: syn::ItemTrait,
+    mut trait_path: syn::Path,
+) -> Result<(syn::ItemTrait, syn::Path), syn::Error> {
+    let last = trait_path.segments.last_mut().unwrap();
+    let args = match std::mem::replace(&mut last.arguments, syn::PathArguments::None) {
+        syn::PathArguments::AngleBracketed(args) => args,
+        _ => return Ok((traitdef, trait_path)),
+    };
+    let span = args.span();
+    let (lifetimes, others): (Vec<_>, Vec<_>) = args
+        .args
+        .into_iter()
+        .partition(|arg| matches!(arg, syn::GenericArgument::Lifetime(_)));
+    let num_lifetime_params = traitdef.generics.lifetimes().count();
+    let num_other_params = traitdef.generics.params.len() - num_lifetime_params;
+    if lifetimes.len() != num_lifetime_params || others.len() != num_other_params {
+        return Err(syn::Error::new(
+            span,
+            format!(
+                "Expected {} lifetime and {} type or const arguments for `{}`, found {} and {}",
+                num_lifetime_params,
+                num_other_params,
+                traitdef.ident,
+                lifetimes.len(),
+                others.len(),
+            ),
+        ));
+    }
+    let (mut lifetimes, mut others) = (lifetimes.into_iter(), others.into_iter());
+    let mut substitutions = vec![];
+    let mut trait_args = syn::punctuated::Punctuated::<syn::GenericArgument, syn::Token![,]>::new();
+    for param in traitdef.generics.params.iter() {
+        let (ident, arg) = match param {
+            syn::GenericParam::Lifetime(param) => {
+                (&param.lifetime.ident, lifetimes.next().unwrap())
+            }
+            syn::GenericParam::Type(syn::TypeParam { ident, .. })
+            | syn::GenericParam::Const(syn::ConstParam { ident, .. }) => (ident, others.next().unwrap()),
+        };
+        let substitute = match arg {
+            syn::GenericArgument::Type(syn::Type::Infer(_)) => {
+                trait_args.push(syn::parse_quote! { #ident });
+                continue;
+            }
+            syn::GenericArgument::Lifetime(ref lifetime) => GenericSubstitute::Lifetime(lifetime.clone()),
+            syn::GenericArgument::Type(ty) if matches!(param, syn::GenericParam::Const(_)) => {
+                // A const argument that is just a name or path, like `N` or `consts::N`, is parsed
+                // as a type. Only single names are accepted by the compiler without braces.
+                substitutions.push((ident.clone(), GenericSubstitute::Const(syn::parse_quote! { #ty })));
+                trait_args.push(syn::GenericArgument::Const(syn::parse_quote! { { #ty } }));
+                continue;
+            }
+            syn::GenericArgument::Type(ref ty) => GenericSubstitute::Type(ty.clone()),
+            syn::GenericArgument::Const(ref expr) => GenericSubstitute::Const(expr.clone()),
+            _ => return Ok((traitdef, trait_path)),
+        };
+        substitutions.push((ident.clone(), substitute));
+        trait_args.push(arg);
+    }
+    traitdef.generics = syn::Generics::default();
+    let tokens = substitute_generics(traitdef.into_token_stream(), &substitutions);
+    trait_path.segments.last_mut().unwrap().arguments =
+        syn::PathArguments::AngleBracketed(syn::AngleBracketedGenericArguments {
+            colon2_token: Some(Default::default()),
+            lt_token: Default::default(),
+            args: trait_args,
+            gt_token: Default::default(),
+        });
+    // Substitutes can still produce invalid syntax in unusual positions, e.g. in macro invocations.
+    let traitdef = syn::parse2(tokens).map_err(|e| {
+        syn::Error::new(
+            span,
+            format!(
+                "Could not substitute these arguments for the generic parameters of `{}`: {}",
+                trait_path.segments.last().unwrap().ident,
+                e,
+            ),
+        )
+    })?;
+    Ok((traitdef, trait_path))
+}
+
+/// Replaces every use of the generic parameters named by the given identifiers with their
+/// substitutes. Types are wrapped in an invisible group, or in angle brackets when followed by a
+/// path separator as in `<Vec<u8>>::new()`. Constants are wrapped in braces where they appear as
+/// generic arguments, as in `Buffer<{ N }>`, and otherwise in parentheses, as in `(N) + 1`.
+fn substitute_generics(
+    tokens: proc_macro2::TokenStream,
+    substitutions: &[(syn::Ident, GenericSubstitute)],
+) -> proc_macro2::TokenStream {
+    let mut substituted = proc_macro2::TokenStream::new();
+    let mut tokens = tokens.into_iter().peekable();
+    // The punctuation right before the current token, if any. Identifiers following a `'`, `.` or
+    // `::` are never type or const parameters.
+    let mut prev_punct: Option<proc_macro2::Punct> = None;
+    let mut prev_path_separator = false;
+    while let Some(token) = tokens.next() {
+        let after_punct = prev_path_separator
+            || matches!(&prev_punct, Some(punct) if punct.as_char() == '.' || punct.as_char() == '\'');
+        match &token {
+            proc_macro2::TokenTree::Ident(ident) => {
+                let is_lifetime = matches!(&prev_punct, Some(punct) if punct.as_char() == '\'');
+                let substitute = substitutions
+                    .iter()
+                    .find(|(name, substitute)| {
+                        name == ident && is_lifetime == matches!(substitute, GenericSubstitute::Lifetime(_))
+                    })
+                    .map(|(_, substitute)| substitute);
+                let path_follows = matches!(tokens.peek(), Some(proc_macro2::TokenTree::Punct(punct)) if punct.as_char() == ':' && punct.spacing() == proc_macro2::Spacing::Joint);
+                match substitute {
+                    // The `'` of the lifetime was already emitted.
+                    Some(GenericSubstitute::Lifetime(lifetime)) => {
+                        lifetime.ident.to_tokens(&mut substituted)
+                    }
+                    Some(GenericSubstitute::Type(ty)) if !after_punct && path_follows => {
+                        quote! { <#ty> }.to_tokens(&mut substituted)
+                    }
+                    Some(GenericSubstitute::Type(ty)) if !after_punct => {
+                        proc_macro2::Group::new(proc_macro2::Delimiter::None, ty.to_token_stream())
+                            .to_tokens(&mut substituted)
+                    }
+                    Some(GenericSubstitute::Const(expr)) if !after_punct => {
+                        let in_generic_args = matches!(&prev_punct, Some(punct) if punct.as_char() == '<' || punct.as_char() == ',')
+                            && matches!(tokens.peek(), Some(proc_macro2::TokenTree::Punct(punct)) if punct.as_char() == '>' || punct.as_char() == ',');
+                        match expr {
+                            _ if in_generic_args => quote! { { #expr } }.to_tokens(&mut substituted),
+                            syn::Expr::Lit(_) | syn::Expr::Path(_) | syn::Expr::Paren(_) => {
+                                expr.to_tokens(&mut substituted)
+                            }
+                            _ => quote! { (#expr) }.to_tokens(&mut substituted),
+                        }
+                    }
+                    _ => ident.to_tokens(&mut substituted),
+                }
+            }
+            proc_macro2::TokenTree::Group(group) => {
+                let mut substituted_group = proc_macro2::Group::new(
+                    group.delimiter(),
+                    substitute_generics(group.stream(), substitutions),
+                );
+                substituted_group.set_span(group.span());
+                substituted_group.to_tokens(&mut substituted);
+            }
+            token => token.to_tokens(&mut substituted),
+        }
+        prev_path_separator = match (&prev_punct, &token) {
+            (Some(prev), proc_macro2::TokenTree::Punct(punct)) => {
+                prev.as_char() == ':' && prev.spacing() == proc_macro2::Spacing::Joint && punct.as_char() == ':'
+            }
+            _ => false,
+        };
+        prev_punct = match token {
+            proc_macro2::TokenTree::Punct(punct) => Some(punct),
+            _ => None,
+        };
+    }
+    substituted
+}
+
+/// Generates impls of `From` for each variant type and `TryFrom` from the enum to each variant
+/// type, without consulting the cache.
+///
+/// Either kind of impl can be disabled for the whole enum with `no_from` or `no_try_from`, or for
+/// single variants with `#[enum_dispatch(skip_from)]`.
+pub fn add_conversion_impls(
+    enum_def: &EnumDispatchItem,
+    enum_path: &syn::Path,
+) -> proc_macro2::TokenStream {
+    let variants: Vec<&EnumDispatchVariant> = enum_def.variants.iter().collect();
+    let mut impls = proc_macro2::TokenStream::new();
+
+    let no_from = enum_def
+        .options
+        .iter()
+        .any(|option| matches!(option, EnumDispatchArg::NoFrom(_)));
+    let no_try_from = enum_def
+        .options
+        .iter()
+        .any(|option| matches!(option, EnumDispatchArg::NoTryFrom(_)));
+
+    if !no_from {
+        let from_impls = generate_from_impls(enum_path, &variants, &enum_def.generics);
+        for from_impl in from_impls.iter() {
+            from_impl.to_tokens(&mut impls);
+        }
+    }
+
+    if !no_try_from {
+        // The error type and `Kind` enum are generated next to the enum, so they are referred to
+        // through the same module as the enum.
+        let error_path = uses_try_from_error(enum_def)
+            .then(|| sibling_path(enum_path, try_from_error_name(&enum_def.ident)));
+        let kind_path = uses_kind_enum(enum_def)
+            .then(|| sibling_path(enum_path, kind_enum_name(&enum_def.ident)));
+        let try_into_impls = generate_try_into_impls(
+            enum_path,
+            &variants,
+            &enum_def.generics,
+            error_path.as_ref(),
+            kind_path.as_ref(),
+        );
+        for try_into_impl in try_into_impls.iter() {
+            try_into_impl.to_tokens(&mut impls);
+        }
+
+        let try_into_ref_impls = generate_try_into_ref_impls(
+            enum_path,
+            &variants,
+            &enum_def.generics,
+            error_path.as_ref(),
+            kind_path.as_ref(),
+        );
+        for try_into_ref_impl in try_into_ref_impls.iter() {
+            try_into_ref_impl.to_tokens(&mut impls);
+        }
+    }
+
+    if enum_def
+        .options
+        .iter()
+        .any(|option| matches!(option, EnumDispatchArg::Accessors(_)))
+    {
+        generate_accessors(enum_path, &enum_def.vis, &variants, &enum_def.generics).to_tokens(&mut impls);
+    }
+    generate_downcast(enum_path, &enum_def.vis, &variants, &enum_def.generics).to_tokens(&mut impls);
+
+    impls
+}
+
+/// Implements the specified trait for the given enum definition, without consulting the cache.
+///
+/// `const_accessors` holds the accessor methods already generated for the enum's associated
+/// constants, mapped to the constants they return, and is extended with those of this trait.
+pub fn add_trait_impl(
+    enum_def: &EnumDispatchItem,
+    enum_path: &syn::Path,
     traitdef: syn::ItemTrait,
+    trait_path: syn::Path,
+    const_accessors: &mut HashMap<String, String>,
 ) -> proc_macro2::TokenStream {
-    let traitname = traitdef.ident;
+    // Generic variant types are required to implement the trait in everything generated here, but
+    // not in the enum definition itself.
+    let enum_def = &with_variant_bounds(enum_def, &trait_path, &traitdef.generics);
+    let traitname = trait_path;
     let traitfns = traitdef.items;
 
-    let (generic_impl_constraints, enum_type_generics, where_clause) =
-        enum_def.generics.split_for_impl();
+    // Lifetimes given as arguments in the link replace the lifetime parameters of the enum and
+    // trait. The impl declares the enum's remaining parameters, along with any lifetimes of the
+    // trait that weren't replaced by one of them.
+    let mut impl_generics = enum_def.generics.clone();
+    impl_generics.params = impl_generics
+        .params
+        .into_iter()
+        .filter(|param| !matches!(param, syn::GenericParam::Lifetime(param) if param.lifetime.ident == "static"))
+        .collect();
+    let trait_lifetimes = traitdef
+        .generics
+        .lifetimes()
+        .filter(|param| param.lifetime.ident != "static")
+        .filter(|param| !impl_generics.lifetimes().any(|enum_param| enum_param.lifetime == param.lifetime))
+        .cloned()
+        .collect::<Vec<_>>();
+    for (i, lifetime) in trait_lifetimes.into_iter().enumerate() {
+        impl_generics.params.insert(i, syn::GenericParam::Lifetime(lifetime));
+    }
+    let (generic_impl_constraints, _, where_clause) = impl_generics.split_for_impl();
+    let (_, enum_type_generics, _) = enum_def.generics.split_for_impl();
     let (_, trait_type_generics, _) = traitdef.generics.split_for_impl();
 
-    let enumname = &enum_def.ident.to_owned();
+    let enumname = enum_path;
     let trait_impl = quote! {
         impl #generic_impl_constraints #traitname #trait_type_generics for #enumname #enum_type_generics #where_clause {
 
@@ -38,43 +478,364 @@ pub fn add_enum_impls(
 
     let variants: Vec<&EnumDispatchVariant> = enum_def.variants.iter().collect();
 
-    for trait_fn in traitfns {
-        trait_impl.items.push(create_trait_match(
-            trait_fn,
-            &trait_type_generics,
-            &traitname,
-            &enum_def.ident,
-            &variants,
-        ));
+    let mut impls = proc_macro2::TokenStream::new();
+
+    // Associated types are resolved before any methods, since return values of the methods may need
+    // to be wrapped in a generated output enum.
+    let mut output_enums = vec![];
+    for trait_item in traitfns.iter() {
+        if let syn::TraitItem::Type(trait_type) = trait_item {
+            match create_trait_type(
+                trait_type,
+                &trait_type_generics,
+                &traitname,
+                enum_def,
+                &variants,
+            ) {
+                Ok((impl_type, output_enum)) => {
+                    trait_impl.items.push(impl_type);
+                    if let Some(output_enum) = output_enum {
+                        output_enums.push((trait_type.ident.clone(), output_enum.ident.clone()));
+                        output_enum.to_tokens(&mut impls);
+                    }
+                }
+                Err(e) => e.to_compile_error().to_tokens(&mut impls),
+            }
+        }
     }
 
-    let mut impls = proc_macro2::TokenStream::new();
+    let has_kind_enum = uses_kind_enum(enum_def);
+    let has_const_accessors = enum_def
+        .options
+        .iter()
+        .any(|option| matches!(option, EnumDispatchArg::ConstAccessors(_)));
 
-    // Only generate From impls once per enum_def
-    if !cache::conversion_impls_def_by_enum(
-        &enum_def.ident,
-        enum_def.generics.type_params().count(),
-    ) {
-        let from_impls = generate_from_impls(&enum_def.ident, &variants, &enum_def.generics);
-        for from_impl in from_impls.iter() {
-            from_impl.to_tokens(&mut impls);
+    let mut inherent_fns = vec![];
+    for trait_item in traitfns {
+        match trait_item {
+            syn::TraitItem::Type(_) => (),
+            syn::TraitItem::Fn(trait_method)
+                if has_kind_enum
+                    && trait_method.sig.receiver().is_none()
+                    && !trait_method.sig.inputs.iter().any(is_self_typed) =>
+            {
+                match create_kind_constructor(
+                    &trait_method,
+                    &trait_type_generics,
+                    &traitname,
+                    enum_def,
+                    enumname,
+                    &variants,
+                ) {
+                    Ok(constructor) => inherent_fns.push(constructor),
+                    Err(e) => e.to_compile_error().to_tokens(&mut impls),
+                }
+                match create_trait_match(
+                    syn::TraitItem::Fn(trait_method),
+                    &trait_type_generics,
+                    &traitname,
+                    enumname,
+                    &variants,
+                    &output_enums,
+                    &enum_def.options,
+                ) {
+                    Ok(impl_item) => trait_impl.items.extend(impl_item),
+                    Err(e) => e.to_compile_error().to_tokens(&mut impls),
+                }
+            }
+            syn::TraitItem::Const(trait_const) => {
+                match create_trait_const(&trait_const, &enum_def.options) {
+                    Ok(Some(impl_const)) => trait_impl.items.push(impl_const),
+                    Ok(None) => (),
+                    Err(e) => e.to_compile_error().to_tokens(&mut impls),
+                }
+                if has_const_accessors {
+                    match create_const_accessor(
+                        &trait_const,
+                        &trait_type_generics,
+                        &traitname,
+                        enum_def,
+                        enumname,
+                        &variants,
+                        const_accessors,
+                    ) {
+                        Ok(accessor) => inherent_fns.push(accessor),
+                        Err(e) => e.to_compile_error().to_tokens(&mut impls),
+                    }
+                }
+            }
+            trait_item => match create_trait_match(
+                trait_item,
+                &trait_type_generics,
+                &traitname,
+                enumname,
+                &variants,
+                &output_enums,
+                &enum_def.options,
+            ) {
+                Ok(impl_item) => trait_impl.items.extend(impl_item),
+                Err(e) => e.to_compile_error().to_tokens(&mut impls),
+            },
         }
+    }
 
-        let try_into_impls =
-            generate_try_into_impls(&enum_def.ident, &variants, &trait_impl.generics);
-        for try_into_impl in try_into_impls.iter() {
-            try_into_impl.to_tokens(&mut impls);
+    if !inherent_fns.is_empty() {
+        quote! {
+            impl #generic_impl_constraints #enumname #enum_type_generics #where_clause {
+                #(#inherent_fns)*
+            }
         }
-        cache::cache_enum_conversion_impls_defined(
-            enum_def.ident.clone(),
-            enum_def.generics.type_params().count(),
-        );
+        .to_tokens(&mut impls);
     }
 
     trait_impl.to_tokens(&mut impls);
     impls
 }
 
+/// Returns the name of the fieldless `Kind` enum generated for the given enum.
+fn kind_enum_name(enum_name: &syn::Ident) -> syn::Ident {
+    quote::format_ident!("{}Kind", enum_name)
+}
+
+/// Returns the name of the error type returned when parsing the `Kind` enum of the given enum.
+fn kind_parse_error_name(enum_name: &syn::Ident) -> syn::Ident {
+    quote::format_ident!("Parse{}KindError", enum_name)
+}
+
+/// Returns whether a `Kind` enum should be generated for the given enum, requested with
+/// `#[enum_dispatch(kind)]`.
+fn uses_kind_enum(enum_def: &EnumDispatchItem) -> bool {
+    enum_def
+        .options
+        .iter()
+        .any(|option| matches!(option, EnumDispatchArg::Kind(_)))
+}
+
+/// Generates a fieldless enum with one unit variant for each variant of the given enum, if
+/// requested with `#[enum_dispatch(kind)]`.
+///
+/// The `Kind` enum can be displayed as and parsed from the names of the variants. The enum itself
+/// also receives inherent `kind()` and `variant_name()` methods, along with `VARIANT_NAMES` and
+/// `VARIANT_COUNT` constants.
+pub fn generate_kind_enum(enum_def: &EnumDispatchItem) -> proc_macro2::TokenStream {
+    if !uses_kind_enum(enum_def) {
+        return proc_macro2::TokenStream::new();
+    }
+    let vis = &enum_def.vis;
+    let enum_name = &enum_def.ident;
+    let kind_name = kind_enum_name(enum_name);
+    let error_name = kind_parse_error_name(enum_name);
+    let attrs = enum_def
+        .variants
+        .iter()
+        .map(|variant| variant.attrs.iter().filter(use_attribute).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let variant_names = enum_def.variants.iter().map(|variant| &variant.ident).collect::<Vec<_>>();
+    let variant_patterns = enum_def.variants.iter().map(|variant| variant.pattern(None));
+    let name_strings = variant_names.iter().map(|ident| ident.to_string()).collect::<Vec<_>>();
+    let (impl_generics, ty_generics, where_clause) = enum_def.generics.split_for_impl();
+    let kind_doc = format!("The kind of variant held by a `{}`.", enum_name);
+    let error_doc = format!("An error returned when parsing an unknown `{}`.", kind_name);
+    let error_message = format!("unknown {} variant name", enum_name);
+    quote! {
+        #[doc = #kind_doc]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        #vis enum #kind_name {
+            #(#(#attrs)* #variant_names),*
+        }
+
+        impl #kind_name {
+            /// Returns the name of the variant.
+            #vis const fn name(&self) -> &'static str {
+                match self {
+                    #(#(#attrs)* #kind_name::#variant_names => #name_strings,)*
+                }
+            }
+        }
+
+        impl ::core::fmt::Display for #kind_name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.write_str(self.name())
+            }
+        }
+
+        impl ::core::str::FromStr for #kind_name {
+            type Err = #error_name;
+            fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                match s {
+                    #(#(#attrs)* #name_strings => ::core::result::Result::Ok(#kind_name::#variant_names),)*
+                    _ => ::core::result::Result::Err(#error_name),
+                }
+            }
+        }
+
+        #[doc = #error_doc]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        #vis struct #error_name;
+
+        impl ::core::fmt::Display for #error_name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.write_str(#error_message)
+            }
+        }
+
+        // Implemented through `core`, which requires Rust 1.81, so that `no_std` crates can use
+        // the `Kind` enum.
+        impl ::core::error::Error for #error_name {}
+
+        impl #impl_generics #enum_name #ty_generics #where_clause {
+            /// The names of all variants, in order.
+            #vis const VARIANT_NAMES: &'static [&'static str] = &[#(#(#attrs)* #name_strings),*];
+            /// The number of variants.
+            #vis const VARIANT_COUNT: usize = Self::VARIANT_NAMES.len();
+
+            /// Returns the kind of the active variant.
+            #[inline]
+            #vis fn kind(&self) -> #kind_name {
+                match self {
+                    #(#(#attrs)* #enum_name::#variant_patterns => #kind_name::#variant_names,)*
+                }
+            }
+
+            /// Returns the name of the active variant.
+            #[inline]
+            #vis fn variant_name(&self) -> &'static str {
+                self.kind().name()
+            }
+        }
+    }
+}
+
+/// Returns the name of the error type returned by the `TryFrom` impls of the given enum.
+fn try_from_error_name(enum_name: &syn::Ident) -> syn::Ident {
+    quote::format_ident!("{}TryFromError", enum_name)
+}
+
+/// Returns the path to an item generated next to the enum at the given path, e.g.
+/// `shapes::ShapeKind` for `shapes::Shape`.
+fn sibling_path(enum_path: &syn::Path, name: syn::Ident) -> syn::Path {
+    let mut path = enum_path.clone();
+    let last = path.segments.last_mut().unwrap();
+    last.ident = name;
+    last.arguments = syn::PathArguments::None;
+    path
+}
+
+/// Returns whether the `TryFrom` impls of the given enum should fail with a generated error type
+/// instead of a plain message, requested with `#[enum_dispatch(try_from_error)]`.
+fn uses_try_from_error(enum_def: &EnumDispatchItem) -> bool {
+    enum_def
+        .options
+        .iter()
+        .any(|option| matches!(option, EnumDispatchArg::TryFromError(_)))
+}
+
+/// Generates the error type returned by the `TryFrom` impls of the given enum, if it was requested
+/// and they aren't disabled. The error holds the original value, so that a failed conversion
+/// doesn't lose it, and is generic over it so that conversions from references to the enum can
+/// return the reference. The variant that was found is given by the enum's `Kind`, if it has one,
+/// or by its name otherwise.
+pub fn generate_try_from_error(enum_def: &EnumDispatchItem) -> proc_macro2::TokenStream {
+    if !uses_try_from_error(enum_def)
+        || enum_def
+            .options
+            .iter()
+            .any(|option| matches!(option, EnumDispatchArg::NoTryFrom(_)))
+    {
+        return proc_macro2::TokenStream::new();
+    }
+    let vis = &enum_def.vis;
+    let enum_name = &enum_def.ident;
+    let error_name = try_from_error_name(enum_name);
+    let found_type: syn::Type = if uses_kind_enum(enum_def) {
+        let kind_name = kind_enum_name(enum_name);
+        syn::parse_quote! { #kind_name }
+    } else {
+        syn::parse_quote! { &'static str }
+    };
+    let error_doc = format!(
+        "An error returned when converting a `{}`, or a reference to one, into the type of a \
+        variant it doesn't hold.",
+        enum_name
+    );
+    let error_name_str = error_name.to_string();
+    quote! {
+        #[doc = #error_doc]
+        #vis struct #error_name<V> {
+            /// The name of the variant that was expected.
+            pub expected: &'static str,
+            /// The variant that was found instead.
+            pub found: #found_type,
+            /// The original value or reference.
+            #[allow(dead_code)]
+            pub value: V,
+        }
+
+        impl<V> ::core::fmt::Display for #error_name<V> {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                write!(f, "Tried to convert variant {} to {}", self.found, self.expected)
+            }
+        }
+
+        impl<V> ::core::fmt::Debug for #error_name<V> {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.debug_struct(#error_name_str)
+                    .field("expected", &self.expected)
+                    .field("found", &self.found)
+                    .finish_non_exhaustive()
+            }
+        }
+
+        // Implemented through `core`, which requires Rust 1.81, so that `no_std` crates can also
+        // use the error.
+        impl<V> ::core::error::Error for #error_name<V> {}
+    }
+}
+
+/// Builds an inherent constructor-style method for the given enum type that calls the given
+/// static trait method on the variant type selected by a `Kind` argument, e.g.
+/// `fn new_for(kind: MyEnumKind, ...)` for `fn new(...)`.
+fn create_kind_constructor(
+    trait_method: &syn::TraitItemFn,
+    trait_generics: &syn::TypeGenerics,
+    trait_name: &syn::Path,
+    enum_def: &EnumDispatchItem,
+    enum_path: &syn::Path,
+    enumvariants: &[&EnumDispatchVariant],
+) -> Result<syn::ImplItemFn, syn::Error> {
+    let mut trait_method = trait_method.clone();
+    rename_signature_arguments(&mut trait_method)?;
+
+    // The `Kind` enum is generated alongside the enum itself.
+    let mut kind_name = enum_path.clone();
+    let last_segment = kind_name.segments.last_mut().unwrap();
+    last_segment.ident = kind_enum_name(&last_segment.ident);
+    let match_arms = enumvariants.iter().map(|variant| {
+        let attrs = variant.attrs.iter().filter(use_attribute);
+        let variant_name = &variant.ident;
+        let call = create_static_call(&trait_method, trait_generics, trait_name, variant)?;
+        Ok(quote! {
+            #(#attrs)*
+            #kind_name::#variant_name => #call,
+        })
+    }).collect::<Result<Vec<_>, syn::Error>>()?;
+
+    let mut sig = trait_method.sig;
+    sig.ident = quote::format_ident!("{}_for", sig.ident);
+    sig.inputs.insert(0, syn::parse_quote! { kind: #kind_name });
+    let attrs = &trait_method.attrs;
+    let vis = &enum_def.vis;
+    Ok(syn::parse_quote! {
+        #(#attrs)*
+        #[inline]
+        #vis #sig {
+            match kind {
+                #(#match_arms)*
+            }
+        }
+    })
+}
+
 /// Returns whether or not an attribute from an enum variant should be applied to other usages of
 /// that variant's identifier.
 fn use_attribute(attr: &&syn::Attribute) -> bool {
@@ -83,22 +844,23 @@ fn use_attribute(attr: &&syn::Attribute) -> bool {
 
 /// Generates impls of core::convert::From for each enum variant.
 fn generate_from_impls(
-    enumname: &syn::Ident,
+    enumname: &syn::Path,
     enumvariants: &[&EnumDispatchVariant],
     generics: &syn::Generics,
 ) -> Vec<syn::ItemImpl> {
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
     enumvariants
         .iter()
+        .filter(|variant| !variant.skip_from)
         .map(|variant| {
-            let variant_name = &variant.ident;
             let variant_type = &variant.ty;
             let attributes = &variant.attrs.iter().filter(use_attribute).collect::<Vec<_>>();
+            let construct = variant.construct(&syn::parse_quote! { v });
             let impl_block = quote! {
                 #(#attributes)*
                 impl #impl_generics ::core::convert::From<#variant_type> for #enumname #ty_generics #where_clause {
                     fn from(v: #variant_type) -> #enumname #ty_generics {
-                        #enumname::#variant_name(v)
+                        #enumname::#construct
                     }
                 }
             };
@@ -107,15 +869,22 @@ fn generate_from_impls(
 }
 
 /// Generates impls of core::convert::TryInto for each enum variant.
+///
+/// Failed conversions return the original value inside the error type at `error_path`, or just a
+/// message if no path is given. The error reports the variant that was found by its variant of the
+/// `Kind` enum at `kind_path`, if given, or by its name otherwise.
 fn generate_try_into_impls(
-    enumname: &syn::Ident,
+    enumname: &syn::Path,
     enumvariants: &[&EnumDispatchVariant],
     generics: &syn::Generics,
+    error_path: Option<&syn::Path>,
+    kind_path: Option<&syn::Path>,
 ) -> Vec<syn::ItemImpl> {
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
     enumvariants
         .iter()
         .enumerate()
+        .filter(|(_, variant)| !variant.skip_from)
         .map(|(i, variant)| {
             let variant_name = &variant.ident;
             let variant_type = &variant.ty;
@@ -136,29 +905,557 @@ fn generate_try_into_impls(
                     let attrs = other.attrs.iter().filter(use_attribute);
                     quote! { #(#attrs)* }
                 });
-            let other_idents = other
-                .map(|other| other.ident.clone());
-            let from_str = other_idents.clone().map(|ident| ident.to_string());
+            let other_patterns = other
+                .clone()
+                .map(|other| other.pattern(None));
+            let found = other
+                .clone()
+                .map(|other| match kind_path {
+                    Some(kind_path) => {
+                        let other_name = &other.ident;
+                        quote! { #kind_path::#other_name }
+                    }
+                    None => other.ident.to_string().into_token_stream(),
+                })
+                .collect::<Vec<_>>();
+            let from_str = other.map(|other| other.ident.to_string());
             let to_str = core::iter::repeat(variant_name.to_string());
             let repeated = core::iter::repeat(&enumname);
+            let pattern = variant.pattern(Some(&quote::format_ident!("v")));
+
+            if let Some(error_path) = error_path {
+                let impl_block = quote! {
+                    #(#attributes)*
+                    impl #impl_generics ::core::convert::TryFrom<#enumname #ty_generics> for #variant_type #where_clause {
+                        type Error = #error_path<#enumname #ty_generics>;
+                        fn try_from(val: #enumname #ty_generics) -> ::core::result::Result<#variant_type, <Self as ::core::convert::TryFrom<#enumname #ty_generics>>::Error> {
+                            match val {
+                                #enumname::#pattern => {Ok(v)},
+                                #(  #other_attributes
+                                    #repeated::#other_patterns => {
+                                    Err(#error_path {
+                                        expected: #to_str,
+                                        found: #found,
+                                        value: val,
+                                    })}    ),*
+                            }
+                        }
+                    }
+                };
+                return syn::parse(impl_block.into()).unwrap();
+            }
+
+            let impl_block = quote! {
+                #(#attributes)*
+                impl #impl_generics ::core::convert::TryFrom<#enumname #ty_generics> for #variant_type #where_clause {
+                    type Error = &'static str;
+                    fn try_from(val: #enumname #ty_generics) -> ::core::result::Result<#variant_type, <Self as ::core::convert::TryFrom<#enumname #ty_generics>>::Error> {
+                        match val {
+                            #enumname::#pattern => {Ok(v)},
+                            #(  #other_attributes
+                                #repeated::#other_patterns => {
+                                Err(concat!("Tried to convert variant ",
+                                            #from_str, " to ", #to_str))}    ),*
+                        }
+                    }
+                }
+            };
+            syn::parse(impl_block.into()).unwrap()
+        }).collect()
+}
+
+/// Generates impls of core::convert::TryFrom from shared and mutable references to the enum into
+/// references to each variant type. Like the impls for the enum itself, they fail with the error
+/// type at `error_path` if there is one, holding the reference instead of the enum.
+fn generate_try_into_ref_impls(
+    enumname: &syn::Path,
+    enumvariants: &[&EnumDispatchVariant],
+    generics: &syn::Generics,
+    error_path: Option<&syn::Path>,
+    kind_path: Option<&syn::Path>,
+) -> Vec<syn::ItemImpl> {
+    let mut ref_generics = generics.clone();
+    ref_generics.params.insert(0, syn::parse_quote! { '__enum_dispatch_ref });
+    let (impl_generics, _, where_clause) = ref_generics.split_for_impl();
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let mutabilities = [None, Some(<syn::Token![mut]>::default())];
+    enumvariants
+        .iter()
+        .enumerate()
+        .filter(|(_, variant)| !variant.skip_from)
+        .flat_map(|(i, variant)| {
+            let variant_name = &variant.ident;
+            let variant_type = &variant.ty;
+            let attributes = variant.attrs.iter().filter(use_attribute).collect::<Vec<_>>();
+            let others = enumvariants
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| i != *j)
+                .map(|(_, other)| other)
+                .collect::<Vec<_>>();
+            let other_attributes = others.iter().map(|other| {
+                let attrs = other.attrs.iter().filter(use_attribute);
+                quote! { #(#attrs)* }
+            }).collect::<Vec<_>>();
+            let other_patterns = others.iter().map(|other| other.pattern(None)).collect::<Vec<_>>();
+            let from_str = others.iter().map(|other| other.ident.to_string()).collect::<Vec<_>>();
+            let found = others
+                .iter()
+                .map(|other| match kind_path {
+                    Some(kind_path) => {
+                        let other_name = &other.ident;
+                        quote! { #kind_path::#other_name }
+                    }
+                    None => other.ident.to_string().into_token_stream(),
+                })
+                .collect::<Vec<_>>();
+            let to_str = variant_name.to_string();
+            let pattern = variant.pattern(Some(&quote::format_ident!("v")));
+            mutabilities.iter().map(|mutability| {
+                if let Some(error_path) = error_path {
+                    let impl_block = quote! {
+                        #(#attributes)*
+                        impl #impl_generics ::core::convert::TryFrom<&'__enum_dispatch_ref #mutability #enumname #ty_generics> for &'__enum_dispatch_ref #mutability #variant_type #where_clause {
+                            type Error = #error_path<&'__enum_dispatch_ref #mutability #enumname #ty_generics>;
+                            fn try_from(val: &'__enum_dispatch_ref #mutability #enumname #ty_generics) -> ::core::result::Result<Self, Self::Error> {
+                                match val {
+                                    #enumname::#pattern => Ok(v),
+                                    #(  #other_attributes
+                                        #enumname::#other_patterns => {
+                                        Err(#error_path {
+                                            expected: #to_str,
+                                            found: #found,
+                                            value: val,
+                                        })}    ),*
+                                }
+                            }
+                        }
+                    };
+                    return syn::parse(impl_block.into()).unwrap();
+                }
+                let impl_block = quote! {
+                    #(#attributes)*
+                    impl #impl_generics ::core::convert::TryFrom<&'__enum_dispatch_ref #mutability #enumname #ty_generics> for &'__enum_dispatch_ref #mutability #variant_type #where_clause {
+                        type Error = &'static str;
+                        fn try_from(val: &'__enum_dispatch_ref #mutability #enumname #ty_generics) -> ::core::result::Result<Self, Self::Error> {
+                            match val {
+                                #enumname::#pattern => Ok(v),
+                                #(  #other_attributes
+                                    #enumname::#other_patterns => {
+                                    Err(concat!("Tried to convert variant ",
+                                                #from_str, " to ", #to_str))}    ),*
+                            }
+                        }
+                    }
+                };
+                syn::parse(impl_block.into()).unwrap()
+            }).collect::<Vec<_>>()
+        }).collect()
+}
+
+/// Generates inherent methods on the enum for borrowing and testing its variants, both through
+/// per-variant `as_*`, `as_*_mut` and `is_*` methods and through generic methods that select the
+/// variant by its type using the `TryFrom` impls.
+fn generate_accessors(
+    enumname: &syn::Path,
+    vis: &syn::Visibility,
+    enumvariants: &[&EnumDispatchVariant],
+    generics: &syn::Generics,
+) -> syn::ItemImpl {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let variant_methods = enumvariants.iter().map(|variant| {
+        let variant_name = &variant.ident;
+        let variant_type = &variant.ty;
+        let attributes = variant.attrs.iter().filter(use_attribute).collect::<Vec<_>>();
+        let snake_name = snake_case(variant_name);
+        let as_ref = quote::format_ident!("as_{}", snake_name);
+        let as_mut = quote::format_ident!("as_{}_mut", snake_name);
+        let is = quote::format_ident!("is_{}", snake_name);
+        let as_ref_doc = format!("Returns a reference to the inner value if this is a `{}`.", variant_name);
+        let as_mut_doc = format!("Returns a mutable reference to the inner value if this is a `{}`.", variant_name);
+        let is_doc = format!("Returns `true` if this is a `{}`.", variant_name);
+        let pattern = variant.pattern(Some(&quote::format_ident!("v")));
+        let unbound_pattern = variant.pattern(None);
+        let is_method = quote! {
+            #(#attributes)*
+            #[doc = #is_doc]
+            #[inline]
+            #vis fn #is(&self) -> bool {
+                matches!(self, #enumname::#unbound_pattern)
+            }
+        };
+        if variant.unit {
+            return is_method;
+        }
+        quote! {
+            #(#attributes)*
+            #[doc = #as_ref_doc]
+            #[inline]
+            #[allow(unreachable_patterns)]
+            #vis fn #as_ref(&self) -> ::core::option::Option<&#variant_type> {
+                match self {
+                    #enumname::#pattern => ::core::option::Option::Some(v),
+                    _ => ::core::option::Option::None,
+                }
+            }
+
+            #(#attributes)*
+            #[doc = #as_mut_doc]
+            #[inline]
+            #[allow(unreachable_patterns)]
+            #vis fn #as_mut(&mut self) -> ::core::option::Option<&mut #variant_type> {
+                match self {
+                    #enumname::#pattern => ::core::option::Option::Some(v),
+                    _ => ::core::option::Option::None,
+                }
+            }
+
+            #is_method
+        }
+    });
+    syn::parse_quote! {
+        impl #impl_generics #enumname #ty_generics #where_clause {
+            #(#variant_methods)*
+
+            /// Returns a reference to the inner value if it has the given type, e.g.
+            /// `value.variant::<MyType>()`.
+            #[inline]
+            #vis fn variant<'__enum_dispatch_ref, __EnumDispatchVariant>(&'__enum_dispatch_ref self) -> ::core::option::Option<&'__enum_dispatch_ref __EnumDispatchVariant>
+            where
+                &'__enum_dispatch_ref __EnumDispatchVariant: ::core::convert::TryFrom<&'__enum_dispatch_ref Self>,
+            {
+                ::core::convert::TryFrom::try_from(self).ok()
+            }
+
+            /// Returns a mutable reference to the inner value if it has the given type.
+            #[inline]
+            #vis fn variant_mut<'__enum_dispatch_ref, __EnumDispatchVariant>(&'__enum_dispatch_ref mut self) -> ::core::option::Option<&'__enum_dispatch_ref mut __EnumDispatchVariant>
+            where
+                &'__enum_dispatch_ref mut __EnumDispatchVariant: ::core::convert::TryFrom<&'__enum_dispatch_ref mut Self>,
+            {
+                ::core::convert::TryFrom::try_from(self).ok()
+            }
+
+            /// Returns `true` if the inner value has the given type.
+            #[inline]
+            #vis fn is_variant<'__enum_dispatch_ref, __EnumDispatchVariant: '__enum_dispatch_ref>(&'__enum_dispatch_ref self) -> bool
+            where
+                &'__enum_dispatch_ref __EnumDispatchVariant: ::core::convert::TryFrom<&'__enum_dispatch_ref Self>,
+            {
+                <&__EnumDispatchVariant as ::core::convert::TryFrom<&Self>>::try_from(self).is_ok()
+            }
+
+            /// Consumes the enum, returning the inner value if it has the given type.
+            #[inline]
+            #vis fn into_variant<__EnumDispatchVariant>(self) -> ::core::result::Result<__EnumDispatchVariant, <__EnumDispatchVariant as ::core::convert::TryFrom<Self>>::Error>
+            where
+                __EnumDispatchVariant: ::core::convert::TryFrom<Self>,
+            {
+                ::core::convert::TryFrom::try_from(self)
+            }
+        }
+    }
+}
+
+/// Generates an `into_builtin` method on the enum, if it has a dynamic variant marked with
+/// `#[enum_dispatch(dynamic(downcast))]`. The method moves a value held by the dynamic variant into
+/// the first built-in variant of the same type, if there is one, using `Any`. This requires the
+/// trait to have `Any` as a supertrait, and the trait object is upcast to `dyn Any`, which requires
+/// Rust 1.86. The variant's own `Box` path is used for the upcast box, so that no `std` or `alloc`
+/// path is needed.
+fn generate_downcast(
+    enumname: &syn::Path,
+    vis: &syn::Visibility,
+    enumvariants: &[&EnumDispatchVariant],
+    generics: &syn::Generics,
+) -> Option<syn::ItemImpl> {
+    let dynamic = enumvariants.iter().find(|variant| variant.downcast)?;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let binding = quote::format_ident!("v");
+    let pattern = dynamic.pattern(Some(&binding));
+    let mut any_box = dynamic.ty.clone();
+    if let syn::Type::Path(syn::TypePath { path, .. }) = &mut any_box {
+        path.segments.last_mut().unwrap().arguments = syn::PathArguments::AngleBracketed(
+            syn::parse_quote! { <dyn ::core::any::Any> },
+        );
+    }
+    let builtins = enumvariants
+        .iter()
+        .filter(|variant| !variant.unit && !variant.dynamic)
+        .map(|variant| {
+            let variant_type = &variant.ty;
+            let value = syn::parse_quote! {
+                *#binding.downcast::<#variant_type>().ok().unwrap()
+            };
+            let construct = variant.construct(&value);
+            let attrs = variant.attrs.iter().filter(use_attribute);
+            quote! {
+                #(#attrs)*
+                if any.is::<#variant_type>() {
+                    if let #enumname::#pattern = self {
+                        let #binding: #any_box = #binding;
+                        return #enumname::#construct;
+                    }
+                    ::core::unreachable!()
+                }
+            }
+        });
+    let doc = format!(
+        "Converts a value held by the dynamic `{}` variant into the built-in variant of its concrete type, if there is one. Any other value is returned unchanged.",
+        dynamic.ident
+    );
+    Some(syn::parse_quote! {
+        impl #impl_generics #enumname #ty_generics #where_clause {
+            #[doc = #doc]
+            #vis fn into_builtin(self) -> Self {
+                let any: &dyn ::core::any::Any = match &self {
+                    #enumname::#pattern => &**#binding,
+                    _ => return self,
+                };
+                #(#builtins)*
+                self
+            }
+        }
+    })
+}
+
+/// Builds an implementation of the given trait constant for the given enum type.
+///
+/// Constants without a value specified in an `#[enum_dispatch(NAME = ...)]` attribute on the enum
+/// fall back to the trait's default, if there is one. Otherwise, an error is returned.
+fn create_trait_const(
+    trait_const: &syn::TraitItemConst,
+    options: &[EnumDispatchArg],
+) -> Result<Option<syn::ImplItem>, syn::Error> {
+    let value = options.iter().rev().find_map(|option| match option {
+        EnumDispatchArg::ConstValue(ident, value) if *ident == trait_const.ident => Some(value),
+        _ => None,
+    });
+    let value = match (value, &trait_const.default) {
+        (Some(value), _) => value,
+        (None, Some(_)) => return Ok(None),
+        (None, None) => {
+            let message = format!(
+                "Associated constant `{0}` has no default value. Specify one for the enum using `#[enum_dispatch({0} = ...)]`.",
+                trait_const.ident
+            );
+            return Err(syn::Error::new(trait_const.ident.span(), message));
+        }
+    };
+    let attrs = &trait_const.attrs;
+    let ident = &trait_const.ident;
+    let ty = &trait_const.ty;
+    Ok(Some(syn::parse_quote! {
+        #(#attrs)*
+        const #ident: #ty = #value;
+    }))
+}
+
+/// Returns the names of the inherent methods generated for the given enum by its own arguments,
+/// rather than by a linked trait.
+fn reserved_method_names(enum_def: &EnumDispatchItem) -> Vec<String> {
+    let mut names = vec![];
+    for option in enum_def.options.iter() {
+        match option {
+            EnumDispatchArg::Kind(_) => names.extend(["kind".to_string(), "variant_name".to_string()]),
+            EnumDispatchArg::Accessors(_) => {
+                for variant in enum_def.variants.iter() {
+                    let snake_name = snake_case(&variant.ident);
+                    names.push(format!("as_{}", snake_name));
+                    names.push(format!("as_{}_mut", snake_name));
+                    names.push(format!("is_{}", snake_name));
+                }
+                names.extend(
+                    ["variant", "variant_mut", "is_variant", "into_variant"]
+                        .iter()
+                        .map(|name| name.to_string()),
+                );
+            }
+            _ => (),
+        }
+    }
+    if enum_def.variants.iter().any(|variant| variant.downcast) {
+        names.push("into_builtin".to_string());
+    }
+    names
+}
+
+/// Builds an inherent method for the given enum type that returns the value of the given trait
+/// constant for the currently active variant, e.g. `fn name(&self)` for `const NAME`.
+///
+/// An error is returned if the method would have the same name as another generated method, either
+/// an accessor in `const_accessors` or a method generated for one of the enum's arguments.
+fn create_const_accessor(
+    trait_const: &syn::TraitItemConst,
+    trait_generics: &syn::TypeGenerics,
+    trait_name: &syn::Path,
+    enum_def: &EnumDispatchItem,
+    enum_name: &syn::Path,
+    enumvariants: &[&EnumDispatchVariant],
+    const_accessors: &mut HashMap<String, String>,
+) -> Result<syn::ImplItemFn, syn::Error> {
+    let attrs = &trait_const.attrs;
+    let vis = &enum_def.vis;
+    let const_name = &trait_const.ident;
+    let fn_name = syn::Ident::new(&const_name.to_string().to_lowercase(), const_name.span());
+    let qualified_name = format!("{}::{}", trait_name.segments.last().unwrap().ident, const_name);
+    if reserved_method_names(enum_def).contains(&fn_name.to_string()) {
+        return Err(syn::Error::new(
+            const_name.span(),
+            format!(
+                "The accessor `{}` for `{}` has the same name as a method generated by another argument to #[enum_dispatch(...)] on `{}`",
+                fn_name, qualified_name, enum_def.ident
+            ),
+        ));
+    }
+    if let Some(other) = const_accessors.get(&fn_name.to_string()) {
+        return Err(syn::Error::new(
+            const_name.span(),
+            format!(
+                "The accessor `{}` for `{}` has the same name as the accessor for `{}` on `{}`",
+                fn_name, qualified_name, other, enum_def.ident
+            ),
+        ));
+    }
+    const_accessors.insert(fn_name.to_string(), qualified_name);
+    let ty = &trait_const.ty;
+    let match_arms = enumvariants.iter().map(|variant| {
+        let pattern = variant.pattern(None);
+        // Unit variants have no inner type, and trait objects can't have constants, so they use
+        // the enum's own value.
+        let variant_type = if variant.unit || variant.dynamic {
+            syn::parse_quote! { Self }
+        } else {
+            variant.ty.clone()
+        };
+        let attrs = variant.attrs.iter().filter(use_attribute);
+        quote! {
+            #(#attrs)*
+            #enum_name::#pattern => <#variant_type as #trait_name #trait_generics>::#const_name,
+        }
+    });
+    Ok(syn::parse_quote! {
+        #(#attrs)*
+        #[inline]
+        #vis fn #fn_name(&self) -> #ty {
+            match self {
+                #(#match_arms)*
+            }
+        }
+    })
+}
+
+/// Paths of derivable traits that will be derived for a generated output enum if they appear as a
+/// bound on the corresponding associated type.
+const DERIVABLE_OUTPUT_BOUNDS: &[(&str, &str)] = &[
+    ("Debug", "::core::fmt::Debug"),
+    ("Clone", "::core::clone::Clone"),
+    ("Copy", "::core::marker::Copy"),
+    ("PartialEq", "::core::cmp::PartialEq"),
+    ("Eq", "::core::cmp::Eq"),
+    ("PartialOrd", "::core::cmp::PartialOrd"),
+    ("Ord", "::core::cmp::Ord"),
+    ("Hash", "::core::hash::Hash"),
+];
+
+/// Names of traits that a generated output enum implements automatically if the associated types
+/// of all variants do, so they are allowed as bounds on the corresponding associated type.
+const AUTO_OUTPUT_BOUNDS: &[&str] = &["Sized", "Send", "Sync", "Unpin"];
+
+/// Builds an implementation of the given trait associated type for the given enum type.
+///
+/// Associated types specified in an `#[enum_dispatch(type Output = ...)]` attribute on the enum
+/// use that type directly. Otherwise, an output enum named after the enum, the trait and the
+/// associated type (e.g. `MyEnumMyTraitOutput`) is generated, with one variant holding each
+/// variant type's own associated type. The generated enum is returned alongside the impl item.
+fn create_trait_type(
+    trait_type: &syn::TraitItemType,
+    trait_generics: &syn::TypeGenerics,
+    trait_name: &syn::Path,
+    enum_def: &EnumDispatchItem,
+    enumvariants: &[&EnumDispatchVariant],
+) -> Result<(syn::ImplItem, Option<syn::ItemEnum>), syn::Error> {
+    if !trait_type.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            trait_type.generics.span(),
+            "Generic associated types cannot be enum_dispatched",
+        ));
+    }
+    let attrs = &trait_type.attrs;
+    let ident = &trait_type.ident;
+    let explicit_type = enum_def.options.iter().rev().find_map(|option| match option {
+        EnumDispatchArg::AssocType(option_ident, ty) if option_ident == ident => Some(ty),
+        _ => None,
+    });
+    if let Some(ty) = explicit_type {
+        return Ok((
+            syn::parse_quote! {
+                #(#attrs)*
+                type #ident = #ty;
+            },
+            None,
+        ));
+    }
 
-            let impl_block = quote! {
-                #(#attributes)*
-                impl #impl_generics ::core::convert::TryFrom<#enumname> for #variant_type #ty_generics #where_clause {
-                    type Error = &'static str;
-                    fn try_from(val: #enumname) -> ::core::result::Result<#variant_type, <Self as ::core::convert::TryFrom<#enumname>>::Error> {
-                        match val {
-                            #enumname::#variant_name(v) => {Ok(v)},
-                            #(  #other_attributes
-                                #repeated::#other_idents(v) => {
-                                Err(concat!("Tried to convert variant ",
-                                            #from_str, " to ", #to_str))}    ),*
-                        }
-                    }
-                }
-            };
-            syn::parse(impl_block.into()).unwrap()
-        }).collect()
+    if let Some(variant) = enumvariants.iter().find(|variant| variant.dynamic) {
+        let message = format!(
+            "Associated type `{0}` must be specified using `#[enum_dispatch(type {0} = ...)]`, since the dynamic variant `{1}` has no type to take it from",
+            ident, variant.ident
+        );
+        return Err(syn::Error::new(ident.span(), message));
+    }
+
+    // The trait's name is included, since several traits may declare associated types with the
+    // same name.
+    let enum_name = &enum_def.ident;
+    let trait_ident = &trait_name.segments.last().unwrap().ident;
+    let output_name = quote::format_ident!("{}{}{}", enum_name, trait_ident, ident);
+    let mut derives = vec![];
+    for bound in trait_type.bounds.iter() {
+        let path = match bound {
+            syn::TypeParamBound::Trait(syn::TraitBound { path, .. }) => path,
+            _ => continue,
+        };
+        let last = &path.segments.last().unwrap().ident;
+        if let Some((_, derive)) = DERIVABLE_OUTPUT_BOUNDS.iter().find(|(name, _)| last == name) {
+            derives.push(syn::parse_str::<syn::Path>(derive).unwrap());
+        } else if !AUTO_OUTPUT_BOUNDS.iter().any(|name| last == name) {
+            let message = format!(
+                "Associated type `{0}` is bounded by `{1}`, which can't be derived for the generated output enum `{2}`. Specify the type with `#[enum_dispatch(type {0} = ...)]` instead.",
+                ident,
+                path.to_token_stream().to_string().replace(' ', ""),
+                output_name,
+            );
+            return Err(syn::Error::new_spanned(path, message));
+        }
+    }
+    let vis = &enum_def.vis;
+    let generics = &enum_def.generics;
+    let where_clause = &enum_def.generics.where_clause;
+    // Unit variants have no inner type to take an associated type from.
+    let variants = enumvariants.iter().filter(|variant| !variant.unit).map(|variant| {
+        let attrs = variant.attrs.iter().filter(use_attribute);
+        let variant_name = &variant.ident;
+        let variant_type = &variant.ty;
+        quote! {
+            #(#attrs)*
+            #variant_name(<#variant_type as #trait_name #trait_generics>::#ident)
+        }
+    });
+    let output_enum: syn::ItemEnum = syn::parse_quote! {
+        #[derive(#(#derives),*)]
+        #vis enum #output_name #generics #where_clause {
+            #(#variants),*
+        }
+    };
+    let (_, enum_type_generics, _) = enum_def.generics.split_for_impl();
+    Ok((
+        syn::parse_quote! {
+            #(#attrs)*
+            type #ident = #output_name #enum_type_generics;
+        },
+        Some(output_enum),
+    ))
 }
 
 /// Used to keep track of the 'self' arguments in a trait's function signature.
@@ -217,56 +1514,82 @@ fn extract_fn_args(
 
 /// Creates a method call that can be used in the match arms of all non-static method
 /// implementations.
+///
+/// Static methods have no `self` argument to match on, so they are called on the provided
+/// `static_type` instead.
 fn create_trait_fn_call(
     trait_method: &syn::TraitItemFn,
     trait_generics: &syn::TypeGenerics,
-    trait_name: &syn::Ident,
-) -> syn::Expr {
+    trait_name: &syn::Path,
+    static_type: Option<&syn::Type>,
+) -> Result<syn::Expr, syn::Error> {
     let trait_args = trait_method.to_owned().sig.inputs;
     let (method_type, mut args) = extract_fn_args(trait_args);
 
-    // Insert FIELDNAME at the beginning of the argument list for UCFS-style method calling
-    let explicit_self_arg = syn::Ident::new(FIELDNAME, trait_method.span());
-    args.insert(0, plain_identifier_expr(explicit_self_arg));
+    let method_name = &trait_method.sig.ident;
+
+    // It's not allowed to specify late bound lifetime arguments for a function call.
+    // Theoretically, it should be possible to determine from a function signature whether or not
+    // it has late bound lifetime arguments. In practice, it's very difficult, requiring recursive
+    // visitors over all the types in the signature and inference for elided lifetimes.
+    //
+    // Instead, it appears to be safe to strip out any lifetime arguments altogether.
+    let mut generics_without_lifetimes = trait_method.sig.generics.clone();
+    generics_without_lifetimes.params = generics_without_lifetimes
+        .params
+        .into_iter()
+        .filter(|param| !matches!(param, syn::GenericParam::Lifetime(..)))
+        .collect();
+    let method_type_generics = generics_without_lifetimes.split_for_impl().1;
+    let method_turbofish = method_type_generics.as_turbofish();
+
+    let func: syn::Expr = match (method_type, static_type) {
+        (MethodType::Static, Some(static_type)) => syn::parse_quote! {
+            <#static_type as #trait_name #trait_generics>::#method_name#method_turbofish
+        },
+        (MethodType::Static, None) => {
+            // Trait calls can only be created when the inner type is known. Without a concrete
+            // enum to match on, it's impossible to tell which variant to call.
+            return Err(syn::Error::new_spanned(
+                &trait_method.sig,
+                format!(
+                    "`{}` cannot be enum_dispatched, since it has no `self` argument to match on. Give it a default implementation or specify a default variant using `#[enum_dispatch(static_default = ...)]`.",
+                    method_name,
+                ),
+            ));
+        }
+        (_, static_type) => {
+            // Insert FIELDNAME at the beginning of the argument list for UCFS-style method calling
+            let explicit_self_arg = syn::Ident::new(FIELDNAME, trait_method.span());
+            match receiver_kind(&trait_method.sig) {
+                ReceiverKind::Plain => args.insert(0, plain_identifier_expr(explicit_self_arg)),
+                // SAFETY: the field is never moved out of the pinned enum, so pinning is
+                // structural, as long as the enum itself was pinned in the first place.
+                ReceiverKind::Pinned { .. } => args.insert(0, syn::parse_quote! {
+                    unsafe { ::core::pin::Pin::new_unchecked(#explicit_self_arg) }
+                }),
+                ReceiverKind::Boxed | ReceiverKind::Shared => args.insert(0, syn::parse_quote! {
+                    ::core::convert::From::from(#explicit_self_arg)
+                }),
+            }
+
+            match static_type {
+                // The type of the receiver can't be inferred from a pointer created with `From`,
+                // so the inner type must be named.
+                Some(static_type) => syn::parse_quote! {
+                    <#static_type as #trait_name #trait_generics>::#method_name#method_turbofish
+                },
+                None => {
+                    let trait_turbofish = trait_generics.as_turbofish();
+                    syn::parse_quote! { #trait_name#trait_turbofish::#method_name#method_turbofish }
+                }
+            }
+        }
+    };
 
     let mut call = syn::Expr::from(syn::ExprCall {
         attrs: vec![],
-        func: {
-            if let MethodType::Static = method_type {
-                // Trait calls can be created when the inner type is known, like this:
-                //
-                // syn::parse_quote! { #type::#trait_method_name }
-                //
-                // However, without a concrete enum to match on, it's impossible to tell
-                // which variant to call.
-                unimplemented!(
-                    "Static methods cannot be enum_dispatched (no self argument to match on)"
-                );
-            } else {
-                let method_name = &trait_method.sig.ident;
-                let trait_turbofish = trait_generics.as_turbofish();
-
-                // It's not allowed to specify late bound lifetime arguments for a function call.
-                // Theoretically, it should be possible to determine from a function signature
-                // whether or not it has late bound lifetime arguments. In practice, it's very
-                // difficult, requiring recursive visitors over all the types in the signature and
-                // inference for elided lifetimes.
-                //
-                // Instead, it appears to be safe to strip out any lifetime arguments altogether.
-                let mut generics_without_lifetimes = trait_method.sig.generics.clone();
-                generics_without_lifetimes.params = generics_without_lifetimes
-                    .params
-                    .into_iter()
-                    .filter(|param| !matches!(param, syn::GenericParam::Lifetime(..)))
-                    .collect();
-                let method_type_generics = generics_without_lifetimes.split_for_impl().1;
-                let method_turbofish = method_type_generics.as_turbofish();
-
-                Box::new(
-                    syn::parse_quote! { #trait_name#trait_turbofish::#method_name#method_turbofish },
-                )
-            }
-        },
+        func: Box::new(func),
         paren_token: Default::default(),
         args,
     });
@@ -280,102 +1603,897 @@ fn create_trait_fn_call(
         });
     }
 
-    call
+    Ok(call)
+}
+
+/// Returns true if any method of the given trait takes `self: Pin<&Self>` or `self: Pin<&mut
+/// Self>`, which requires the guards from `generate_pin_guards`.
+pub fn has_pinned_receivers(traitdef: &syn::ItemTrait) -> bool {
+    traitdef.items.iter().any(|item| {
+        matches!(item, syn::TraitItem::Fn(method) if matches!(receiver_kind(&method.sig), ReceiverKind::Pinned { .. }))
+    })
+}
+
+/// Reports methods of the given trait that take `self: Rc<Self>` or `self: Arc<Self>` without a
+/// default implementation, since the active variant can't be moved out of a shared pointer to
+/// dispatch them. This is checked where the trait is defined, rather than for each linked enum.
+pub fn check_shared_receivers(traitdef: &syn::ItemTrait) -> Option<syn::Error> {
+    combine_errors(traitdef.items.iter().filter_map(|item| match item {
+        syn::TraitItem::Fn(method)
+            if method.default.is_none() && matches!(receiver_kind(&method.sig), ReceiverKind::Shared) =>
+        {
+            Some(syn::Error::new_spanned(
+                method.sig.receiver(),
+                format!(
+                    "`{}` cannot be enum_dispatched, since the active variant can't be moved out of a shared pointer. Give it a default implementation, or take `&self` instead.",
+                    method.sig.ident,
+                ),
+            ))
+        }
+        _ => None,
+    }))
+}
+
+/// Generates guards that make pinning the fields of the enum's variants sound, in the same way as
+/// `pin-project`. Pinned receivers project a pinned enum onto the field of its active variant, so
+/// the enum must not be moved out of a pin while a field is `!Unpin`.
+///
+/// That could happen through a manual `impl Unpin` for the enum, which is prevented by an `Unpin`
+/// impl that only applies when all fields are `Unpin`. It could also happen through a `Drop` impl,
+/// which receives an unpinned `&mut self`. That is prevented by a blanket impl of a local trait for
+/// all `Drop` types, which conflicts with an impl for the enum.
+pub fn generate_pin_guards(enum_def: &EnumDispatchItem, enum_path: &syn::Path) -> proc_macro2::TokenStream {
+    let (_, ty_generics, where_clause) = enum_def.generics.split_for_impl();
+    let mut guard_generics = enum_def.generics.clone();
+    guard_generics.params.insert(0, syn::parse_quote! { '__enum_dispatch_pin });
+    let (guard_impl_generics, guard_ty_generics, _) = guard_generics.split_for_impl();
+    let field_types = enum_def
+        .variants
+        .iter()
+        .filter(|variant| !variant.unit)
+        .flat_map(|variant| variant.fields.iter().map(|field| &field.ty));
+    let mut unpin_where_clause = where_clause.cloned().unwrap_or_else(|| syn::parse_quote! { where });
+    unpin_where_clause.predicates.push(syn::parse_quote! {
+        __EnumDispatchPinGuard #guard_ty_generics: ::core::marker::Unpin
+    });
+    let impl_generics = enum_def.generics.split_for_impl().0;
+    quote! {
+        const _: () = {
+            #[allow(dead_code)]
+            struct __EnumDispatchPinGuard #guard_impl_generics #where_clause {
+                __pin: ::core::marker::PhantomData<&'__enum_dispatch_pin ()>,
+                __fields: ::core::marker::PhantomData<(#(#field_types,)*)>,
+            }
+
+            impl #guard_impl_generics ::core::marker::Unpin for #enum_path #ty_generics #unpin_where_clause {}
+
+            trait __EnumDispatchMustNotImplDrop {}
+            #[allow(clippy::drop_bounds, drop_bounds)]
+            impl<T: ::core::ops::Drop> __EnumDispatchMustNotImplDrop for T {}
+            impl #impl_generics __EnumDispatchMustNotImplDrop for #enum_path #ty_generics #where_clause {}
+        };
+    }
+}
+
+/// Describes how the value of a variant is passed to a trait method, based on the method's
+/// receiver.
+enum ReceiverKind {
+    /// `self`, `&self`, `&mut self`, or any other receiver that can be matched on directly.
+    Plain,
+    /// `self: Pin<&Self>` or `self: Pin<&mut Self>`. The enum is unpinned to match on it, and the
+    /// variant's field is pinned again.
+    Pinned { mutable: bool },
+    /// `self: Box<Self>`. The enum is moved out of the box to match on it, and the variant's field
+    /// is moved into a new box.
+    Boxed,
+    /// `self: Rc<Self>` or `self: Arc<Self>`. The variant's field can't be moved out of a shared
+    /// pointer, so these methods can only use the trait's default implementation.
+    Shared,
+}
+
+/// Determines how the receiver of the given method signature should be handled.
+fn receiver_kind(sig: &syn::Signature) -> ReceiverKind {
+    let ty = match sig.receiver() {
+        Some(syn::Receiver {
+            reference: None,
+            colon_token: Some(_),
+            ty,
+            ..
+        }) => ty,
+        _ => return ReceiverKind::Plain,
+    };
+    let segment = match ty.as_ref() {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => path.segments.last().unwrap(),
+        _ => return ReceiverKind::Plain,
+    };
+    let is_self = |ty: &syn::Type| matches!(ty, syn::Type::Path(p) if p.qself.is_none() && p.path.is_ident("Self"));
+    match (segment.ident.to_string().as_str(), type_arguments(segment).as_slice()) {
+        ("Pin", [syn::Type::Reference(reference)]) if is_self(&reference.elem) => {
+            ReceiverKind::Pinned {
+                mutable: reference.mutability.is_some(),
+            }
+        }
+        ("Box", [inner]) if is_self(inner) => ReceiverKind::Boxed,
+        ("Rc", [inner]) | ("Arc", [inner]) if is_self(inner) => ReceiverKind::Shared,
+        _ => ReceiverKind::Plain,
+    }
+}
+
+/// Returns an expression that can be matched on to find the active variant of `self`, according
+/// to the receiver of the given method signature.
+fn create_match_subject(sig: &syn::Signature) -> syn::Expr {
+    let self_expr = plain_identifier_expr(syn::Ident::new("self", proc_macro2::Span::call_site()));
+    match receiver_kind(sig) {
+        ReceiverKind::Plain => self_expr,
+        // SAFETY: the matched fields are immediately re-pinned in each match arm.
+        ReceiverKind::Pinned { mutable: true } => {
+            syn::parse_quote! { unsafe { ::core::pin::Pin::get_unchecked_mut(#self_expr) } }
+        }
+        ReceiverKind::Pinned { mutable: false } => {
+            syn::parse_quote! { ::core::pin::Pin::get_ref(#self_expr) }
+        }
+        ReceiverKind::Boxed => syn::parse_quote! { *#self_expr },
+        ReceiverKind::Shared => unreachable!("Methods with shared pointer receivers are never dispatched"),
+    }
+}
+
+/// Returns true if the given tokens contain a bare `Self` type, as opposed to only paths like
+/// `Self::Output` or `<Self as Trait>::Output`.
+fn contains_self(tokens: proc_macro2::TokenStream) -> bool {
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            proc_macro2::TokenTree::Ident(ident) if ident == "Self" => match tokens.peek() {
+                Some(proc_macro2::TokenTree::Punct(punct)) if punct.as_char() == ':' => (),
+                Some(proc_macro2::TokenTree::Ident(ident)) if ident == "as" => (),
+                _ => return true,
+            },
+            proc_macro2::TokenTree::Group(group) if contains_self(group.stream()) => return true,
+            _ => (),
+        }
+    }
+    false
+}
+
+/// Returns true if the given tokens mention the associated type `Self::<ident>`.
+fn mentions_associated_type(tokens: proc_macro2::TokenStream, ident: &syn::Ident) -> bool {
+    let tokens = tokens.into_iter().collect::<Vec<_>>();
+    tokens.iter().enumerate().any(|(i, token)| match token {
+        proc_macro2::TokenTree::Ident(self_ident) if self_ident == "Self" => matches!(
+            &tokens[i + 1..],
+            [proc_macro2::TokenTree::Punct(first), proc_macro2::TokenTree::Punct(second), proc_macro2::TokenTree::Ident(name), ..]
+                if first.as_char() == ':' && second.as_char() == ':' && name == ident
+        ),
+        proc_macro2::TokenTree::Group(group) => mentions_associated_type(group.stream(), ident),
+        _ => false,
+    })
+}
+
+/// Returns a copy of the enum definition with predicates requiring the types of its variants to
+/// implement the given trait added to its where clause. These are the predicates given with
+/// `bound(...)` if there are any, or else inferred for each variant type that mentions a type or
+/// const parameter of the enum.
+///
+/// Variants without a type implementing the trait, ones disabled by `#[cfg(...)]` attributes, and
+/// ones whose type refers to the enum itself, which would make the bound recursive, are skipped.
+fn with_variant_bounds(
+    enum_def: &EnumDispatchItem,
+    trait_path: &syn::Path,
+    trait_generics: &syn::Generics,
+) -> EnumDispatchItem {
+    let mut enum_def = enum_def.clone();
+    let explicit_bounds = enum_def.options.iter().rev().find_map(|option| match option {
+        EnumDispatchArg::Bound(_, predicates) => Some(predicates.iter().cloned().collect()),
+        _ => None,
+    });
+    let predicates: Vec<syn::WherePredicate> = explicit_bounds.unwrap_or_else(|| {
+        let params = enum_def
+            .generics
+            .params
+            .iter()
+            .filter_map(|param| match param {
+                syn::GenericParam::Type(param) => Some(&param.ident),
+                syn::GenericParam::Const(param) => Some(&param.ident),
+                syn::GenericParam::Lifetime(_) => None,
+            })
+            .collect::<Vec<_>>();
+        let (_, trait_type_generics, _) = trait_generics.split_for_impl();
+        enum_def
+            .variants
+            .iter()
+            .filter(|variant| !variant.unit && !variant.dynamic)
+            .filter(|variant| !variant.attrs.iter().any(|attr| attr.path().is_ident("cfg")))
+            .filter(|variant| {
+                let ty = variant.ty.to_token_stream();
+                mentions_any(ty.clone(), &params) && !mentions_any(ty, &[&enum_def.ident])
+            })
+            .map(|variant| {
+                let variant_type = &variant.ty;
+                syn::parse_quote! { #variant_type: #trait_path #trait_type_generics }
+            })
+            .collect()
+    });
+    if !predicates.is_empty() {
+        enum_def.generics.make_where_clause().predicates.extend(predicates);
+    }
+    enum_def
+}
+
+/// Returns true if any of the given identifiers appear in the tokens.
+fn mentions_any(tokens: proc_macro2::TokenStream, idents: &[&syn::Ident]) -> bool {
+    tokens.into_iter().any(|token| match token {
+        proc_macro2::TokenTree::Ident(ident) => idents.iter().any(|other| ident == **other),
+        proc_macro2::TokenTree::Group(group) => mentions_any(group.stream(), idents),
+        _ => false,
+    })
+}
+
+/// Returns the type arguments of the last segment of a path.
+fn type_arguments(segment: &syn::PathSegment) -> Vec<&syn::Type> {
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => args
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                syn::GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Converts an expression of the given type, as returned by the inner type of a variant, into the
+/// same type as returned by the enum. Any `Self` types within are converted into the enum type by
+/// wrapping them in the given variant, mapping through containers like `Option`, `Result`, `Vec`,
+/// arrays, and tuples. The variant is constructed directly rather than through `From`, so that this
+/// still works for variants without a `From` impl.
+///
+/// `depth` is used to give unique names to the bindings of nested closures and patterns.
+fn convert_self_return(
+    ty: &syn::Type,
+    expr: syn::Expr,
+    variant: &EnumDispatchVariant,
+    depth: usize,
+) -> Result<syn::Expr, syn::Error> {
+    if !contains_self(ty.to_token_stream()) {
+        return Ok(expr);
+    }
+    let binding = quote::format_ident!("__enum_dispatch_value_{}", depth);
+    // Converts the value bound to `binding` with the given type.
+    let convert_binding = |ty: &syn::Type| {
+        convert_self_return(ty, plain_identifier_expr(binding.clone()), variant, depth + 1)
+    };
+    let unsupported = || {
+        syn::Error::new_spanned(
+            ty,
+            "Unsupported use of `Self` in the return type of an enum_dispatched method. `Self` can only be returned directly, or within an `Option`, `Result`, `Poll`, `Vec`, array or tuple",
+        )
+    };
+    match ty {
+        syn::Type::Paren(syn::TypeParen { elem, .. })
+        | syn::Type::Group(syn::TypeGroup { elem, .. }) => {
+            convert_self_return(elem, expr, variant, depth)
+        }
+        syn::Type::Path(syn::TypePath { qself: None, path }) if path.is_ident("Self") => {
+            let construct = variant.construct(&expr);
+            Ok(syn::parse_quote! { Self::#construct })
+        }
+        syn::Type::Path(syn::TypePath { qself: None, path }) => {
+            let segment = path.segments.last().unwrap();
+            match (segment.ident.to_string().as_str(), type_arguments(segment).as_slice()) {
+                ("Option", [inner]) | ("Poll", [inner]) | ("Result", [inner]) => {
+                    let converted = convert_binding(inner)?;
+                    Ok(syn::parse_quote! { #expr.map(|#binding| #converted) })
+                }
+                ("Result", [ok, err]) => {
+                    let converted_ok = convert_binding(ok)?;
+                    let converted_err = convert_binding(err)?;
+                    let mut expr = expr;
+                    if contains_self(ok.to_token_stream()) {
+                        expr = syn::parse_quote! { #expr.map(|#binding| #converted_ok) };
+                    }
+                    if contains_self(err.to_token_stream()) {
+                        expr = syn::parse_quote! { #expr.map_err(|#binding| #converted_err) };
+                    }
+                    Ok(expr)
+                }
+                ("Vec", [inner])
+                | ("VecDeque", [inner])
+                | ("LinkedList", [inner])
+                | ("BTreeSet", [inner]) => {
+                    let converted = convert_binding(inner)?;
+                    Ok(syn::parse_quote! {
+                        ::core::iter::Iterator::collect(
+                            ::core::iter::Iterator::map(
+                                ::core::iter::IntoIterator::into_iter(#expr),
+                                |#binding| #converted,
+                            )
+                        )
+                    })
+                }
+                _ => Err(unsupported()),
+            }
+        }
+        syn::Type::Array(array) => {
+            let converted = convert_binding(&array.elem)?;
+            Ok(syn::parse_quote! { #expr.map(|#binding| #converted) })
+        }
+        syn::Type::Tuple(tuple) => {
+            let bindings = (0..tuple.elems.len())
+                .map(|i| quote::format_ident!("{}_{}", binding, i))
+                .collect::<Vec<_>>();
+            let converted = tuple
+                .elems
+                .iter()
+                .zip(bindings.iter())
+                .map(|(ty, binding)| {
+                    convert_self_return(ty, plain_identifier_expr(binding.clone()), variant, depth + 1)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(syn::parse_quote! {
+                {
+                    let (#(#bindings,)*) = #expr;
+                    (#(#converted,)*)
+                }
+            })
+        }
+        _ => Err(unsupported()),
+    }
+}
+
+/// Converts the result of a call to a trait method on a variant's inner type into the return type
+/// of the method for the enum. See `convert_self_return`.
+fn convert_call_return(
+    sig: &syn::Signature,
+    call: syn::Expr,
+    variant: &EnumDispatchVariant,
+) -> Result<syn::Expr, syn::Error> {
+    match &sig.output {
+        syn::ReturnType::Type(_, returntype) => convert_self_return(returntype, call, variant, 0),
+        syn::ReturnType::Default => Ok(call),
+    }
+}
+
+/// Creates a call of a static trait method on the given variant's type. If the method returns
+/// `Self`, the returned value is converted into the enum type.
+fn create_static_call(
+    trait_method: &syn::TraitItemFn,
+    trait_generics: &syn::TypeGenerics,
+    trait_name: &syn::Path,
+    variant: &EnumDispatchVariant,
+) -> Result<syn::Expr, syn::Error> {
+    if variant.unit {
+        return Ok(create_unit_call(trait_method, variant));
+    }
+    if variant.dynamic {
+        return Ok(create_dynamic_panic(trait_method, variant));
+    }
+    let variant_type = &variant.ty;
+    let call = create_trait_fn_call(trait_method, trait_generics, trait_name, Some(variant_type))?;
+    convert_call_return(&trait_method.sig, call, variant)
+}
+
+/// Returns the generated output enum for a return type of exactly `Self::Assoc`, if `Assoc` is
+/// implemented with one.
+fn output_enum_return<'a>(
+    returntype: &syn::Type,
+    output_enums: &'a [(syn::Ident, syn::Ident)],
+) -> Option<&'a syn::Ident> {
+    match returntype {
+        syn::Type::Path(syn::TypePath { qself: None, path })
+            if path.segments.len() == 2 && path.segments[0].ident == "Self" =>
+        {
+            output_enums
+                .iter()
+                .find(|(assoc_type, _)| path.segments[1].ident == *assoc_type)
+                .map(|(_, output_enum)| output_enum)
+        }
+        _ => None,
+    }
+}
+
+/// Returns the generated output enum for a return type of `Option<Self::Assoc>` or
+/// `Poll<Self::Assoc>`, if `Assoc` is implemented with one.
+fn mapped_output_enum_return<'a>(
+    returntype: &syn::Type,
+    output_enums: &'a [(syn::Ident, syn::Ident)],
+) -> Option<&'a syn::Ident> {
+    let segment = match returntype {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != "Option" && segment.ident != "Poll" {
+        return None;
+    }
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            syn::GenericArgument::Type(inner) => output_enum_return(inner, output_enums),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Renames the arguments of a trait method with `identify_signature_arguments`. If the method has
+/// a default implementation, its original argument patterns are bound to the renamed arguments at
+/// the start of it, so that it can still be used.
+fn rename_signature_arguments(trait_method: &mut syn::TraitItemFn) -> Result<(), syn::Error> {
+    let patterns = trait_method
+        .sig
+        .inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            syn::FnArg::Typed(pat_type) => Some(pat_type.pat.clone()),
+            syn::FnArg::Receiver(_) => None,
+        })
+        .collect::<Vec<_>>();
+    identify_signature_arguments(&mut trait_method.sig)?;
+    let args = typed_arg_idents(&trait_method.sig);
+    if let Some(block) = &mut trait_method.default {
+        if !args.is_empty() {
+            let rebind = syn::parse_quote! { let (#(#patterns,)*) = (#(#args,)*); };
+            block.stmts.insert(0, rebind);
+        }
+    }
+    Ok(())
+}
+
+/// Returns the identifiers of the non-`self` arguments of a signature whose arguments have been
+/// renamed by `identify_signature_arguments`.
+fn typed_arg_idents(sig: &syn::Signature) -> Vec<&syn::Ident> {
+    sig.inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            syn::FnArg::Typed(syn::PatType { pat, .. }) => match &**pat {
+                syn::Pat::Ident(pat_ident) => Some(&pat_ident.ident),
+                _ => None,
+            },
+            syn::FnArg::Receiver(_) => None,
+        })
+        .collect()
+}
+
+/// Returns true if the given type is exactly `Self`.
+fn is_self_type(ty: &syn::Type) -> bool {
+    matches!(ty, syn::Type::Path(syn::TypePath { qself: None, path }) if path.is_ident("Self"))
+}
+
+/// Builds the expression evaluated for a unit variant, which has no inner value to dispatch to.
+/// In order of preference, this calls the variant's fallback for the method with the same
+/// arguments, evaluates the method's default implementation, returns the variant itself from
+/// methods returning `Self`, or returns the `Default` value of the return type.
+fn create_unit_call(trait_method: &syn::TraitItemFn, variant: &EnumDispatchVariant) -> syn::Expr {
+    let sig = &trait_method.sig;
+    if let Some(fallback) = variant.fallback(&sig.ident) {
+        let receiver = sig.receiver().map(|_| quote! { self }).into_iter();
+        let args = typed_arg_idents(sig);
+        return syn::parse_quote! { #fallback(#(#receiver,)* #(#args),*) };
+    }
+    if let Some(default_body) = &trait_method.default {
+        return syn::parse_quote! { #default_body };
+    }
+    match &sig.output {
+        syn::ReturnType::Type(_, ty) if is_self_type(ty) => {
+            let variant_name = &variant.ident;
+            syn::parse_quote! { Self::#variant_name }
+        }
+        _ => syn::parse_quote! { ::core::default::Default::default() },
+    }
+}
+
+/// Returns true if the given method could be called on a trait object, given a suitable receiver.
+/// Methods that use `Self` in their signature, have type or const parameters, are `async` or
+/// require `Self: Sized` can't be.
+fn object_callable(sig: &syn::Signature) -> bool {
+    let uses_self = sig.inputs.iter().any(|arg| match arg {
+        syn::FnArg::Typed(pat_type) => contains_self(pat_type.ty.to_token_stream()),
+        syn::FnArg::Receiver(_) => false,
+    }) || match &sig.output {
+        syn::ReturnType::Type(_, ty) => contains_self(ty.to_token_stream()),
+        syn::ReturnType::Default => false,
+    };
+    let generic = sig
+        .generics
+        .params
+        .iter()
+        .any(|param| !matches!(param, syn::GenericParam::Lifetime(_)));
+    !(uses_self || generic || sig.asyncness.is_some() || requires_sized(sig))
+}
+
+/// Returns the reference (`&` or `&mut`) to a trait object that the given method can be called
+/// through, or `None` if it can't be called through a reference to a trait object at all, e.g.
+/// because it takes `self` by value or isn't `object_callable`.
+fn trait_object_reference(sig: &syn::Signature) -> Option<proc_macro2::TokenStream> {
+    if !object_callable(sig) {
+        return None;
+    }
+    match (sig.receiver()?, receiver_kind(sig)) {
+        (syn::Receiver { reference: Some(_), mutability, .. }, _) => Some(quote! { &#mutability }),
+        (_, ReceiverKind::Pinned { mutable: true }) => Some(quote! { &mut }),
+        (_, ReceiverKind::Pinned { mutable: false }) => Some(quote! { & }),
+        _ => None,
+    }
+}
+
+/// Returns true if the where clause of the given method signature bounds `Self` by `Sized`.
+fn requires_sized(sig: &syn::Signature) -> bool {
+    let where_clause = match &sig.generics.where_clause {
+        Some(where_clause) => where_clause,
+        None => return false,
+    };
+    where_clause.predicates.iter().any(|predicate| match predicate {
+        syn::WherePredicate::Type(predicate) if is_self_type(&predicate.bounded_ty) => {
+            predicate.bounds.iter().any(|bound| match bound {
+                syn::TypeParamBound::Trait(bound) => bound
+                    .path
+                    .segments
+                    .last()
+                    .is_some_and(|segment| segment.ident == "Sized"),
+                _ => false,
+            })
+        }
+        _ => false,
+    })
+}
+
+/// Builds the expression evaluated for a dynamic variant when the method can't be called on the
+/// trait object it holds.
+fn create_dynamic_panic(trait_method: &syn::TraitItemFn, variant: &EnumDispatchVariant) -> syn::Expr {
+    let message = format!(
+        "`{}` cannot be called on the trait object held by the dynamic variant `{}`",
+        trait_method.sig.ident, variant.ident
+    );
+    syn::parse_quote! { ::core::panic!("{}", #message) }
+}
+
+/// Returns true if the given argument is not the receiver and has a type of `Self`, `&Self`, or
+/// `&mut Self`.
+fn is_self_typed(arg: &syn::FnArg) -> bool {
+    match arg {
+        syn::FnArg::Typed(pat_type) => {
+            let ty = match pat_type.ty.as_ref() {
+                syn::Type::Reference(reference) => reference.elem.as_ref(),
+                ty => ty,
+            };
+            matches!(ty, syn::Type::Path(ty) if ty.qself.is_none() && ty.path.is_ident("Self"))
+        }
+        syn::FnArg::Receiver(_) => false,
+    }
+}
+
+/// Returns the names of the arguments of a method, other than the receiver, whose type is `Self`,
+/// `&Self`, or `&mut Self`. The arguments must already have been renamed by
+/// `identify_signature_arguments`.
+fn self_typed_args(sig: &syn::Signature) -> Vec<syn::Ident> {
+    sig.inputs
+        .iter()
+        .filter(|arg| is_self_typed(arg))
+        .filter_map(|arg| match arg {
+            syn::FnArg::Typed(syn::PatType { pat, .. }) => match pat.as_ref() {
+                syn::Pat::Ident(pat) => Some(pat.ident.clone()),
+                _ => None,
+            },
+            syn::FnArg::Receiver(_) => None,
+        })
+        .collect()
+}
+
+/// Builds the match arm used when a method taking other `Self` arguments is called with
+/// mismatched variants. A configured string is used as a panic message, and any other expression
+/// is called as a function with all of the method's arguments, in order.
+fn create_mismatch_arm(
+    trait_method: &syn::TraitItemFn,
+    self_args: &[syn::Ident],
+    mismatch: Option<&syn::Expr>,
+) -> syn::Arm {
+    let message = match mismatch {
+        Some(syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(message),
+            ..
+        })) => message.value(),
+        Some(fallback) => {
+            // `self` can't be rebound by a pattern, so it is bound under another name.
+            let self_binding = quote::format_ident!("__enum_dispatch_self");
+            let receiver = trait_method.sig.receiver().map(|_| &self_binding);
+            let bindings = receiver.iter().copied().chain(self_args.iter());
+            let args = trait_method.sig.inputs.iter().map(|arg| match arg {
+                syn::FnArg::Receiver(_) => self_binding.to_token_stream(),
+                syn::FnArg::Typed(pat_type) => pat_type.pat.to_token_stream(),
+            });
+            return syn::parse_quote! {
+                #[allow(unreachable_patterns)]
+                (#(#bindings,)*) => #fallback(#(#args),*),
+            };
+        }
+        None => format!(
+            "Mismatched variants passed to `{}`. Specify a fallback using `#[enum_dispatch(mismatch = ...)]`.",
+            trait_method.sig.ident
+        ),
+    };
+    syn::parse_quote! {
+        #[allow(unreachable_patterns)]
+        _ => ::core::panic!("{}", #message),
+    }
 }
 
 /// Constructs a match expression that matches on all variants of the specified enum, creating a
 /// binding to their single field and calling the provided trait method on each.
+///
+/// If the method takes other arguments of type `Self` (e.g. `fn merge(&mut self, other: Self)`),
+/// those are matched alongside `self`, and the trait method is only called on the inner values
+/// when all of them are the same variant. Otherwise, the `mismatch` fallback is used.
 fn create_match_expr(
     trait_method: &syn::TraitItemFn,
     trait_generics: &syn::TypeGenerics,
-    trait_name: &syn::Ident,
-    enum_name: &syn::Ident,
+    trait_name: &syn::Path,
+    enum_name: &syn::Path,
     enumvariants: &[&EnumDispatchVariant],
-) -> syn::Expr {
-    let trait_fn_call = create_trait_fn_call(trait_method, trait_generics, trait_name);
-
-    let is_self_return = if let syn::ReturnType::Type(_, returntype) = &trait_method.sig.output {
-        match returntype.as_ref() {
-            syn::Type::Path(p) => {
-                if let Some(i) = p.path.get_ident() {
-                    i.to_string() == "Self"
-                } else {
-                    false
-                }
+    output_enums: &[(syn::Ident, syn::Ident)],
+    mismatch: Option<&syn::Expr>,
+) -> Result<syn::Expr, syn::Error> {
+    let has_receiver = trait_method.sig.receiver().is_some();
+    let self_args = self_typed_args(&trait_method.sig);
+    let trait_fn_call = match receiver_kind(&trait_method.sig) {
+        _ if !has_receiver => None,
+        ReceiverKind::Boxed | ReceiverKind::Shared => None,
+        _ => Some(create_trait_fn_call(trait_method, trait_generics, trait_name, None)?),
+    };
+    let object_reference = trait_fn_call
+        .as_ref()
+        .and_then(|_| trait_object_reference(&trait_method.sig));
+    // The trait object of a dynamic variant can only be borrowed mutably if it is owned.
+    let mutable_reference = match receiver_kind(&trait_method.sig) {
+        ReceiverKind::Pinned { mutable } => mutable,
+        _ => trait_method
+            .sig
+            .receiver()
+            .is_some_and(|receiver| receiver.reference.is_some() && receiver.mutability.is_some()),
+    };
+    let object_reference_for = |variant: &EnumDispatchVariant| {
+        object_reference
+            .as_ref()
+            .filter(|_| !mutable_reference || variant.owns_trait_object())
+    };
+    // Methods taking `self: Box<Self>` can be passed the box owned by a dynamic variant directly.
+    let boxed_object_call = has_receiver
+        && matches!(receiver_kind(&trait_method.sig), ReceiverKind::Boxed)
+        && object_callable(&trait_method.sig);
+    // Calls the method on the trait object held by a dynamic variant, if possible.
+    let dynamic_call = |variant: &EnumDispatchVariant| -> Option<Result<syn::Expr, syn::Error>> {
+        match (object_reference_for(variant), &trait_fn_call) {
+            (Some(reference), Some(call)) => {
+                let fieldname = syn::Ident::new(FIELDNAME, variant.span());
+                Some(Ok(syn::parse_quote! {
+                    {
+                        let #fieldname = #reference **#fieldname;
+                        #call
+                    }
+                }))
             }
-            _ => false,
+            _ if boxed_object_call && variant.owns_trait_object() => {
+                let object_type = match &variant.ty {
+                    syn::Type::Path(syn::TypePath { path, .. }) => {
+                        type_arguments(path.segments.last().unwrap()).first().copied()?
+                    }
+                    _ => return None,
+                };
+                Some(create_trait_fn_call(trait_method, trait_generics, trait_name, Some(object_type)))
+            }
+            _ => None,
         }
-    } else {
-        false
+    };
+
+    // Methods returning `Self::Output` where `Output` is implemented with a generated output enum
+    // must wrap the returned value in the corresponding variant of that enum. Return types like
+    // `Option<Self::Output>` or `Poll<Self::Output>` are wrapped using their `map` method.
+    let (output_enum, mapped) = match &trait_method.sig.output {
+        syn::ReturnType::Type(_, returntype) => match output_enum_return(returntype, output_enums) {
+            Some(output_enum) => (Some(output_enum), false),
+            None => (mapped_output_enum_return(returntype, output_enums), true),
+        },
+        syn::ReturnType::Default => (None, false),
     };
 
     // Creates a Vec containing a match arm for every enum variant
-    let match_arms = enumvariants
+    let mut match_arms = enumvariants
         .iter()
         .map(|variant| {
-            let mut call = trait_fn_call.to_owned();
+            let variant_name = &variant.ident;
 
-            if is_self_return {
-                let variant_type = &variant.ty;
-                let from_call: syn::ExprCall = syn::parse_quote! {
-                    <Self as ::core::convert::From::<#variant_type>>::from(#call)
+            let mut call = if variant.unit {
+                create_unit_call(trait_method, variant)
+            } else if variant.dynamic {
+                dynamic_call(variant).unwrap_or_else(|| Ok(create_dynamic_panic(trait_method, variant)))?
+            } else {
+                // Static methods taking `Self` arguments, and methods with pointer receivers, are
+                // called on the inner type of the matched variant.
+                let call = match &trait_fn_call {
+                    Some(call) => call.clone(),
+                    None => create_trait_fn_call(trait_method, trait_generics, trait_name, Some(&variant.ty))?,
                 };
-                call = syn::Expr::from(from_call);
+                convert_call_return(&trait_method.sig, call, variant)?
+            };
+
+            match output_enum {
+                // Unit variants already return a value of the enum's return type, and dynamic
+                // variants can't be used with output enums.
+                _ if variant.unit || variant.dynamic => (),
+                Some(output_enum) if mapped => {
+                    call = syn::parse_quote! { #call.map(#output_enum::#variant_name) };
+                }
+                Some(output_enum) => {
+                    call = syn::parse_quote! { #output_enum::#variant_name(#call) };
+                }
+                None => (),
             }
 
-            let variant_name = &variant.ident;
             let attrs = variant
                 .attrs
                 .iter()
                 .filter(use_attribute)
                 .cloned()
                 .collect::<Vec<_>>();
-            syn::Arm {
+            // Other `Self` arguments are bound to the inner values under their own names, so the
+            // inner values are passed along by the call.
+            let fieldname = syn::Ident::new(FIELDNAME, variant.span());
+            let bound = !variant.dynamic || dynamic_call(variant).is_some();
+            let bindings = has_receiver
+                .then_some(&fieldname)
+                .into_iter()
+                .chain(self_args.iter());
+            let patterns = bindings.map(|binding| variant.pattern(bound.then_some(binding)));
+            let pat = if self_args.is_empty() {
+                syn::parse_quote! {#(#enum_name::#patterns)*}
+            } else {
+                syn::parse_quote! {(#(#enum_name::#patterns,)*)}
+            };
+            Ok(syn::Arm {
                 attrs,
-                pat: {
-                    let fieldname = syn::Ident::new(FIELDNAME, variant.span());
-                    syn::parse_quote! {#enum_name::#variant_name(#fieldname)}
-                },
+                pat,
                 guard: None,
                 fat_arrow_token: Default::default(),
                 body: Box::new(call),
                 comma: Some(Default::default()),
-            }
+            })
         })
-        .collect();
+        .collect::<Result<Vec<_>, syn::Error>>()?;
+
+    let self_expr = create_match_subject(&trait_method.sig);
+
+    let match_subject = if self_args.is_empty() {
+        self_expr
+    } else {
+        let subjects = has_receiver
+            .then_some(self_expr)
+            .into_iter()
+            .chain(self_args.iter().cloned().map(plain_identifier_expr));
+        match_arms.push(create_mismatch_arm(trait_method, &self_args, mismatch));
+        syn::parse_quote! { (#(#subjects,)*) }
+    };
 
     // Creates the match expression
-    syn::Expr::from(syn::ExprMatch {
+    Ok(syn::Expr::from(syn::ExprMatch {
         attrs: vec![],
         match_token: Default::default(),
-        expr: Box::new(plain_identifier_expr(syn::Ident::new(
-            "self",
-            proc_macro2::Span::call_site(),
-        ))),
+        expr: Box::new(match_subject),
         brace_token: Default::default(),
         arms: match_arms,
-    })
+    }))
 }
 
-/// Builds an implementation of the given trait function for the given enum type.
+/// Builds an implementation of the given trait function for the given enum type. Methods that
+/// can't be dispatched but have a default implementation are left out, returning `None`.
 fn create_trait_match(
     trait_item: syn::TraitItem,
     trait_generics: &syn::TypeGenerics,
-    trait_name: &syn::Ident,
-    enum_name: &syn::Ident,
+    trait_name: &syn::Path,
+    enum_name: &syn::Path,
     enumvariants: &[&EnumDispatchVariant],
-) -> syn::ImplItem {
+    output_enums: &[(syn::Ident, syn::Ident)],
+    options: &[EnumDispatchArg],
+) -> Result<Option<syn::ImplItem>, syn::Error> {
     match trait_item {
         syn::TraitItem::Fn(mut trait_method) => {
-            identify_signature_arguments(&mut trait_method.sig);
-
-            let match_expr = create_match_expr(
-                &trait_method,
-                trait_generics,
-                trait_name,
-                enum_name,
-                enumvariants,
-            );
+            // Methods without a default implementation are reported by `check_shared_receivers`
+            // where the trait is defined.
+            if let ReceiverKind::Shared = receiver_kind(&trait_method.sig) {
+                return Ok(None);
+            }
+            // Values of an output enum can't be unwrapped into the associated type of the active
+            // variant, so they can't be passed on to the inner method.
+            let output_arg = trait_method.sig.inputs.iter().find_map(|arg| match arg {
+                syn::FnArg::Typed(pat_type) => output_enums
+                    .iter()
+                    .find(|(ident, _)| mentions_associated_type(pat_type.ty.to_token_stream(), ident))
+                    .map(|(ident, _)| (pat_type, ident)),
+                syn::FnArg::Receiver(_) => None,
+            });
+            if let Some((pat_type, ident)) = output_arg {
+                return Err(syn::Error::new_spanned(
+                    pat_type,
+                    format!(
+                        "`{}` cannot be enum_dispatched, since it takes `Self::{}` as an argument, which is implemented with a generated output enum. Specify the type with `#[enum_dispatch(type {1} = ...)]` instead.",
+                        trait_method.sig.ident, ident,
+                    ),
+                ));
+            }
+            rename_signature_arguments(&mut trait_method)?;
+
+            let static_default = options.iter().rev().find_map(|option| match option {
+                EnumDispatchArg::StaticDefault(_, variant) => Some(variant),
+                _ => None,
+            });
+            // A fallback for this method takes precedence over a fallback for all methods.
+            let mismatch = options
+                .iter()
+                .rev()
+                .find_map(|option| match option {
+                    EnumDispatchArg::Mismatch(_, Some(method), fallback)
+                        if *method == trait_method.sig.ident =>
+                    {
+                        Some(fallback)
+                    }
+                    _ => None,
+                })
+                .or_else(|| {
+                    options.iter().rev().find_map(|option| match option {
+                        EnumDispatchArg::Mismatch(_, None, fallback) => Some(fallback),
+                        _ => None,
+                    })
+                });
+
+            let is_static = trait_method.sig.receiver().is_none()
+                && !trait_method.sig.inputs.iter().any(is_self_typed);
+            // Without a default variant, static methods with a default implementation keep it.
+            if is_static && static_default.is_none() && trait_method.default.is_some() {
+                return Ok(None);
+            }
+
+            let match_expr = if !is_static {
+                create_match_expr(
+                    &trait_method,
+                    trait_generics,
+                    trait_name,
+                    enum_name,
+                    enumvariants,
+                    output_enums,
+                    mismatch,
+                )?
+            } else {
+                // Static methods are delegated to the type of the variant chosen with
+                // `static_default = ...`.
+                let enum_ident = &enum_name.segments.last().unwrap().ident;
+                let default_variant = static_default.and_then(|static_default| {
+                    enumvariants
+                        .iter()
+                        .find(|variant| variant.ident == *static_default)
+                });
+                match (static_default, default_variant) {
+                    (_, Some(variant)) => create_static_call(
+                        &trait_method,
+                        trait_generics,
+                        trait_name,
+                        variant,
+                    )?,
+                    (Some(static_default), None) => {
+                        let message = format!("No variant named `{}` in `{}`", static_default, enum_ident);
+                        syn::parse_quote_spanned! {static_default.span()=>
+                            ::core::compile_error!(#message)
+                        }
+                    }
+                    (None, None) => {
+                        let message = format!(
+                            "Static method `{}` cannot be enum_dispatched without a default variant. Specify one using `#[enum_dispatch(static_default = ...)]` on `{}`.",
+                            trait_method.sig.ident,
+                            enum_ident,
+                        );
+                        syn::parse_quote_spanned! {trait_method.sig.ident.span()=>
+                            ::core::compile_error!(#message)
+                        }
+                    }
+                }
+            };
 
             let mut impl_attrs = trait_method.attrs.clone();
             // Inline impls - #[inline] is never already specified in a trait method signature
@@ -386,7 +2504,7 @@ fn create_trait_match(
                 meta: syn::Meta::Path(syn::parse_str("inline").unwrap()),
             });
 
-            syn::ImplItem::Fn(syn::ImplItemFn {
+            Ok(Some(syn::ImplItem::Fn(syn::ImplItemFn {
                 attrs: impl_attrs,
                 vis: syn::Visibility::Inherited,
                 defaultness: None,
@@ -395,9 +2513,12 @@ fn create_trait_match(
                     brace_token: Default::default(),
                     stmts: vec![syn::Stmt::Expr(match_expr, None)],
                 },
-            })
+            })))
         }
-        _ => panic!("Unsupported trait item"),
+        trait_item => Err(syn::Error::new_spanned(
+            trait_item,
+            "Unsupported trait item. Only methods, associated types and associated constants can be enum_dispatched",
+        )),
     }
 }
 
@@ -409,7 +2530,7 @@ fn create_trait_match(
 /// Since there is no way to generate hygienic identifiers, we just use a special underscored
 /// string followed by an incrementing counter. We do this for *every* argument, including ones
 /// that are already named, in case somebody clever decides to name their arguments similarly.
-fn identify_signature_arguments(sig: &mut syn::Signature) {
+fn identify_signature_arguments(sig: &mut syn::Signature) -> Result<(), syn::Error> {
     let mut arg_counter = 0;
 
     /// Generates a new argument identifier named `__enum_dispatch_arg_` followed by an
@@ -420,48 +2541,55 @@ fn identify_signature_arguments(sig: &mut syn::Signature) {
         ident
     }
 
-    sig.inputs.iter_mut().for_each(|arg| match arg {
-        syn::FnArg::Typed(ref mut pat_type) => {
-            let span = pat_type.span();
-            *pat_type.pat = match &*pat_type.pat {
-                syn::Pat::Ident(ref pat_ident) => syn::Pat::Ident(syn::PatIdent {
-                    ident: new_arg_ident(pat_ident.span(), &mut arg_counter),
-                    ..pat_ident.clone()
-                }),
-                // Some of these aren't valid Rust syntax, but why not support all of them anyways!
-                syn::Pat::Lit(syn::PatLit { attrs, .. })
-                | syn::Pat::Macro(syn::PatMacro { attrs, .. })
-                | syn::Pat::Or(syn::PatOr { attrs, .. })
-                | syn::Pat::Path(syn::PatPath { attrs, .. })
-                | syn::Pat::Range(syn::PatRange { attrs, .. })
-                | syn::Pat::Reference(syn::PatReference { attrs, .. })
-                | syn::Pat::Rest(syn::PatRest { attrs, .. })
-                | syn::Pat::Slice(syn::PatSlice { attrs, .. })
-                | syn::Pat::Struct(syn::PatStruct { attrs, .. })
-                | syn::Pat::Tuple(syn::PatTuple { attrs, .. })
-                | syn::Pat::TupleStruct(syn::PatTupleStruct { attrs, .. })
-                | syn::Pat::Type(syn::PatType { attrs, .. })
-                | syn::Pat::Const(syn::PatConst { attrs, .. })
-                | syn::Pat::Paren(syn::PatParen { attrs, .. })
-                | syn::Pat::Wild(syn::PatWild { attrs, .. }) => syn::Pat::Ident(syn::PatIdent {
-                    attrs: attrs.to_owned(),
-                    by_ref: None,
-                    mutability: None,
-                    ident: new_arg_ident(span, &mut arg_counter),
-                    subpat: None,
-                }),
-                // This can occur for `box foo` syntax, which is no longer supported by syn 2.0.
-                syn::Pat::Verbatim(_) => syn::Pat::Ident(syn::PatIdent {
-                    attrs: Default::default(),
-                    by_ref: None,
-                    mutability: None,
-                    ident: new_arg_ident(span, &mut arg_counter),
-                    subpat: None,
-                }),
-                _ => panic!("Unsupported argument type"),
+    for arg in sig.inputs.iter_mut() {
+        let pat_type = match arg {
+            syn::FnArg::Typed(pat_type) => pat_type,
+            // `self` arguments will never need to be renamed.
+            syn::FnArg::Receiver(..) => continue,
+        };
+        let span = pat_type.span();
+        *pat_type.pat = match &*pat_type.pat {
+            syn::Pat::Ident(ref pat_ident) => syn::Pat::Ident(syn::PatIdent {
+                ident: new_arg_ident(pat_ident.span(), &mut arg_counter),
+                ..pat_ident.clone()
+            }),
+            // Some of these aren't valid Rust syntax, but why not support all of them anyways!
+            syn::Pat::Lit(syn::PatLit { attrs, .. })
+            | syn::Pat::Macro(syn::PatMacro { attrs, .. })
+            | syn::Pat::Or(syn::PatOr { attrs, .. })
+            | syn::Pat::Path(syn::PatPath { attrs, .. })
+            | syn::Pat::Range(syn::PatRange { attrs, .. })
+            | syn::Pat::Reference(syn::PatReference { attrs, .. })
+            | syn::Pat::Rest(syn::PatRest { attrs, .. })
+            | syn::Pat::Slice(syn::PatSlice { attrs, .. })
+            | syn::Pat::Struct(syn::PatStruct { attrs, .. })
+            | syn::Pat::Tuple(syn::PatTuple { attrs, .. })
+            | syn::Pat::TupleStruct(syn::PatTupleStruct { attrs, .. })
+            | syn::Pat::Type(syn::PatType { attrs, .. })
+            | syn::Pat::Const(syn::PatConst { attrs, .. })
+            | syn::Pat::Paren(syn::PatParen { attrs, .. })
+            | syn::Pat::Wild(syn::PatWild { attrs, .. }) => syn::Pat::Ident(syn::PatIdent {
+                attrs: attrs.to_owned(),
+                by_ref: None,
+                mutability: None,
+                ident: new_arg_ident(span, &mut arg_counter),
+                subpat: None,
+            }),
+            // This can occur for `box foo` syntax, which is no longer supported by syn 2.0.
+            syn::Pat::Verbatim(_) => syn::Pat::Ident(syn::PatIdent {
+                attrs: Default::default(),
+                by_ref: None,
+                mutability: None,
+                ident: new_arg_ident(span, &mut arg_counter),
+                subpat: None,
+            }),
+            pat => {
+                return Err(syn::Error::new_spanned(
+                    pat,
+                    "Unsupported argument pattern in enum_dispatched method",
+                ))
             }
         }
-        // `self` arguments will never need to be renamed.
-        syn::FnArg::Receiver(..) => (),
-    });
+    }
+    Ok(())
 }
@@ -0,0 +1,66 @@
+//! Provides an implementation of a `syn`- and `quote`-compatible syntax item describing the
+//! contents of a `foreign_trait!` invocation.
+//!
+//! Each item in the invocation looks like a trait definition, except that the trait's name is
+//! replaced by the path to a trait defined elsewhere, e.g. `trait some_crate::Handler { ... }`.
+//! The body only needs to contain the signatures of the trait's items, which are used to generate
+//! impls of the real trait.
+
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
+use quote::quote;
+
+/// A mirror of a trait defined in another crate.
+pub struct ForeignTrait {
+    /// The path to the real trait.
+    pub path: syn::Path,
+    /// The mirror definition, named after the last segment of `path`.
+    pub traitdef: syn::ItemTrait,
+}
+
+/// A structure that can be used to store syntax information about a `foreign_trait!` invocation.
+pub struct ForeignTraitList {
+    pub traits: Vec<ForeignTrait>,
+}
+
+/// Allows `ForeignTraitList`s to be parsed from `String`s or `TokenStream`s.
+impl syn::parse::Parse for ForeignTraitList {
+    fn parse(input: syn::parse::ParseStream) -> syn::parse::Result<Self> {
+        let mut traits = vec![];
+        while !input.is_empty() {
+            traits.push(input.parse()?);
+        }
+        Ok(Self { traits })
+    }
+}
+
+/// Allows `ForeignTrait`s to be parsed from `String`s or `TokenStream`s.
+impl syn::parse::Parse for ForeignTrait {
+    fn parse(input: syn::parse::ParseStream) -> syn::parse::Result<Self> {
+        let attrs = input.call(syn::Attribute::parse_outer)?;
+        let unsafety: Option<syn::Token![unsafe]> = input.parse()?;
+        input.parse::<syn::Token![trait]>()?;
+        let path = input.call(syn::Path::parse_mod_style)?;
+
+        // Everything up to and including the braced body belongs to the mirror definition.
+        let rest = input.step(|cursor| {
+            let mut rest = TokenStream::new();
+            let mut next = *cursor;
+            while let Some((tt, after)) = next.token_tree() {
+                next = after;
+                let is_body = matches!(&tt, TokenTree::Group(group) if group.delimiter() == Delimiter::Brace);
+                rest.extend(Some(tt));
+                if is_body {
+                    return Ok((rest, next));
+                }
+            }
+            Err(cursor.error("Expected the body of the trait"))
+        })?;
+
+        let ident = &path.segments.last().unwrap().ident;
+        let traitdef = syn::parse2(quote! {
+            #(#attrs)*
+            #unsafety trait #ident #rest
+        })?;
+        Ok(Self { path, traitdef })
+    }
+}
@@ -15,7 +15,185 @@
 //!
 //! For full documentation of features like generic support, custom variant names, and more, please
 //! check the repository's
-//! [README](https://gitlab.com/antonok/enum_dispatch/-/blob/master/README.md).
+//! [README](https://gitlab.com/antonok/enum_dispatch/-/blob/master/README.md). The arguments and
+//! variant attributes described below are supported in addition to those.
+//!
+//! ## Linking
+//!
+//! Links and arguments can be split across several `#[enum_dispatch(...)]` attributes on the same
+//! item, e.g. `#[enum_dispatch(Shape, kind)]` followed by `#[enum_dispatch(Named, accessors)]`.
+//! They are all handled together, as if they were given in a single attribute.
+//!
+//! A linked item with lifetime parameters can be given lifetime arguments for all of them, e.g.
+//! `#[enum_dispatch(Decode<'a>)]` on `enum Frame<'a>` for `trait Decode<'de>`. The arguments, which
+//! can be lifetimes of the annotated item or `'static`, replace the linked item's own lifetime
+//! parameters in the generated impl, i.e. `impl<'a> Decode<'a> for Frame<'a>`. If the link has no
+//! generic arguments at all, like `#[enum_dispatch(Frame)]`, the lifetime parameters are kept.
+//!
+//! Likewise, a generic trait linked from an enum is implemented for exactly the type and const
+//! arguments given for it, which can be concrete, e.g. `#[enum_dispatch(Handler<Request>)]` or
+//! `#[enum_dispatch(Encode<Vec<u8>>)]`. Listing several instantiations, like
+//! `#[enum_dispatch(Handler<Get>, Handler<Post>)]`, generates one impl for each. An argument of `_`
+//! keeps the trait's own parameter name. Const arguments can be any constant expression, like
+//! `#[enum_dispatch(Buffer<{ SIZE * 2 }>)]` or `#[enum_dispatch(Buffer<consts::LEN>)]`.
+//!
+//! A link must be given exactly as many type and const arguments as the linked item has type and
+//! const parameters. If the linked item is only defined with a different number, an error is
+//! reported.
+//!
+//! In generic enums like `enum Wrapper<T> { Plain, Custom(T) }`, each generated trait impl requires
+//! the variant types that mention a type or const parameter to implement the trait, i.e. `impl<T>
+//! MyTrait for Wrapper<T> where T: MyTrait`, so the bound doesn't need to be declared on the enum.
+//! The inferred bounds can be replaced with a `bound(T: MyTrait + Send)` argument on the enum, or
+//! left out entirely with `bound()`.
+//!
+//! Links from an enum can also bind the trait's associated types, e.g.
+//! `#[enum_dispatch(iter::Iterator<Item = u8>)]` or `#[enum_dispatch(Service<Req, Response =
+//! Resp>)]`, which sets them in the generated impl instead of generating an output enum. Bounds
+//! like `iter::Iterator<Item: Copy>` add `where` predicates requiring them of every variant's
+//! associated type.
+//!
+//! ## Paths and modules
+//!
+//! Traits and enums in `#[enum_dispatch(...)]` attributes can be referred to by path, e.g.
+//! `#[enum_dispatch(crate::engine::ops::Apply)]`. The path is used as written in the generated
+//! code. Since procedural macros cannot tell which module an item is defined in, definitions are
+//! matched by name only. To disambiguate same-named items in different modules, a definition can
+//! declare its module with `#[enum_dispatch(module = crate::engine::ops)]`; links that use an
+//! absolute path starting with `crate::` will then only be fulfilled by the item declared in that
+//! module. Declaring the module also allows impls generated at the other item's definition site to
+//! refer to the item by its full path.
+//!
+//! ## Standard library traits
+//!
+//! Enums can also implement well-known traits from the standard library without declaring them,
+//! e.g. `#[enum_dispatch(fmt::Display, iter::Iterator)]`. The supported traits are `fmt::Display`,
+//! `fmt::Debug`, `iter::Iterator`, `iter::DoubleEndedIterator`, `iter::ExactSizeIterator`,
+//! `hash::Hash`, `future::Future`, `fmt::Write`, and (requiring `std`) `error::Error`, `io::Read`,
+//! `io::Write`, and `io::Seek`. They must be qualified by at least their module, or given by their
+//! full paths like `std::fmt::Display`, while a bare name like `Display` always refers to a
+//! user-defined trait. Bare names can't be resolved to the standard library traits, because a
+//! user-defined trait of the same name may be expanded after the enum, and linking to it later
+//! would then implement both traits for the enum.
+//!
+//! ## Traits from other crates
+//!
+//! Traits can be shared with other crates by annotating them with `#[enum_dispatch(export)]`.
+//! Enums in other crates can then link to the trait using an extern path with a leading `::`, e.g.
+//! `#[enum_dispatch(::core_traits::Plugin)]`. Any types used in the trait's method signatures must
+//! also be in scope where the enum is defined. Exported traits are identified by name within
+//! their crate, so a crate can only export one trait with a given name.
+//!
+//! Without the leading `::`, a path like `core_traits::Plugin` refers to an item in the current
+//! crate, and the link is silently left unresolved if there is none. `check!()` reports such links
+//! along with the extern path to use instead.
+//!
+//! ## Variants
+//!
+//! Variants can carry additional data in other fields, either unnamed or named, by marking the
+//! field holding the dispatched value with `#[dispatch]`, e.g. `Circle(#[dispatch] Circle,
+//! Metadata)` or `Circle { #[dispatch] shape: Circle, id: u32 }`. The other fields are ignored when
+//! dispatching. Where the enum is created from the dispatched value alone, i.e. in `From` impls and
+//! in methods returning `Self`, they are set to their `Default` values. Variants whose other fields
+//! don't implement `Default` need `#[enum_dispatch(skip_from)]`, and can't be used with methods
+//! returning `Self`.
+//!
+//! A variant without any data can be added with `#[enum_dispatch(unit)]`, e.g. for a `Disabled`
+//! state. Since a unit variant has no value to dispatch to, its trait methods call the function
+//! given with `#[enum_dispatch(unit, fallback(method) = path::to::fn)]` if there is one, which
+//! receives the same arguments as the method. Otherwise, they use the trait's default
+//! implementation of the method, return the unit variant itself from methods returning `Self`, or
+//! return `Default::default()`. No conversion impls are generated for unit variants.
+//!
+//! One variant can hold a trait object instead, e.g. `#[enum_dispatch(dynamic)] Other(Box<dyn
+//! MyTrait>)` or `Arc<dyn MyTrait>`, to allow implementations beyond the built-in variants. Methods
+//! on that variant are called through the trait object, and methods taking `self: Box<Self>` are
+//! passed the variant's own box. Methods which can't be called on a trait object, like ones
+//! requiring `Self: Sized`, panic for it. So do methods borrowing `self` mutably or taking `self:
+//! Box<Self>` when the trait object is held by an `Arc` or `Rc`. Associated types must be given
+//! with `#[enum_dispatch(type Name = ...)]`. If the trait has `Any` as a supertrait, marking a
+//! boxed variant with `#[enum_dispatch(dynamic(downcast))]` also generates `into_builtin(self) ->
+//! Self`, which moves a trait object of a built-in variant's type into that variant. It upcasts the
+//! trait object to `dyn Any`, which requires Rust 1.86.
+//!
+//! ## Conversions
+//!
+//! Besides `From`, a linked enum also gets `TryFrom` impls from the enum, `&MyEnum` and `&mut
+//! MyEnum` into each variant type (or references to it). With an `accessors` argument on the enum,
+//! each variant also gets inherent `as_*`, `as_*_mut` and `is_*` methods named after it, e.g.
+//! `as_linear_knob(&self) -> Option<&LinearKnob>` for a `LinearKnob` variant, and the variant can
+//! be selected by type with the generic `variant::<T>()`, `variant_mut::<T>()`,
+//! `is_variant::<T>()` and `into_variant::<T>()` methods.
+//!
+//! When a conversion from the enum into a variant type fails, the `TryFrom` impl returns a
+//! `&'static str` error message. With a `try_from_error` argument on the enum, it instead returns
+//! a generated `MyEnumTryFromError<MyEnum>` holding the `expected` variant name and the variant that
+//! was `found`, along with the original enum in its `value` field. Conversions from `&MyEnum` and
+//! `&mut MyEnum` fail with a `MyEnumTryFromError<&MyEnum>` or `MyEnumTryFromError<&mut MyEnum>`
+//! holding the reference instead. If the enum has a `kind` argument, `found` is a `MyEnumKind`,
+//! and otherwise it is the variant's name. The error is generated next to the enum, so like the
+//! variant types, it must be in scope wherever the enum is linked through an import. It implements
+//! `Display`, `Debug` and `core::error::Error`, which requires Rust 1.81.
+//!
+//! The generated `From` and `TryFrom` impls can be turned off with `no_from` and `no_try_from`
+//! arguments on the enum, e.g. to write them by hand. Individual variants can opt out of both with
+//! an `#[enum_dispatch(skip_from)]` attribute, which is needed when several variants share a type,
+//! or when a variant's type is a generic parameter of the enum.
+//!
+//! ## Methods
+//!
+//! Trait methods that return `Self` convert the value returned for the active variant into the enum
+//! by wrapping it in the same variant. This also works for `Self` within an `Option`, `Result`,
+//! `Poll`, `Vec` (or similar collection), array, or tuple in the return type.
+//!
+//! Methods taking other arguments of type `Self`, `&Self` or `&mut Self` (e.g. `fn distance(&self,
+//! other: &Self)`) are only dispatched when all of those arguments hold the same variant as `self`.
+//! Otherwise, they panic. A custom panic message can be given with a `mismatch = "message"`
+//! argument on the enum, or a function to call instead with `mismatch(distance) = path::to::fn`.
+//! The function receives the same arguments as the method, with `self` replaced by the enum.
+//!
+//! Besides `self`, `&self` and `&mut self`, methods can take `self: Box<Self>`, `self: Rc<Self>`,
+//! `self: Arc<Self>`, `self: Pin<&Self>` or `self: Pin<&mut Self>`. The active variant's value is
+//! passed to the inner method behind the same kind of pointer. Since the active variant can't be
+//! moved out of a shared `Rc` or `Arc`, methods with those receivers are left to their default
+//! implementation, and a trait with such a method that has no default is rejected where it is
+//! defined. Pinned receivers project the pin onto the active variant, so the enum implements
+//! `Unpin` only if all of its variants do, and it may not implement `Drop`.
+//!
+//! ## Associated items
+//!
+//! Associated constants in a trait are implemented for the enum using the trait's default value.
+//! Constants without a default, or whose default should be overridden, can be given a value with
+//! an additional `NAME = value` argument in the enum's `#[enum_dispatch(...)]` attribute. With a
+//! `const_accessors` argument, the enum also receives an inherent method for each constant with its
+//! lowercased name (e.g. `fn name(&self)` for `const NAME`), returning the value for the currently
+//! active variant. It is an error for an accessor to have the same name as another one, or as a
+//! method generated by another argument, like `kind()`.
+//!
+//! Associated types in a trait can be set for the enum with a `type Output = SomeType` argument.
+//! If no type is specified, a companion enum named after the enum, the trait and the associated
+//! type (e.g. `MyEnumMyTraitOutput`) is generated, with a variant holding each variant type's
+//! associated type. Trait methods returning `Self::Output` then wrap their return value in the
+//! matching variant. Common derivable traits like `Debug` or `PartialEq` are derived for the
+//! companion enum when they appear as bounds on the associated type. Other bounds, apart from auto
+//! traits like `Send`, and methods taking `Self::Output` as an argument, can't be supported by a
+//! companion enum and are reported as errors, so such associated types must be set explicitly.
+//!
+//! ## Static methods and `Kind` enums
+//!
+//! Static trait methods (those without a `self` argument) have no variant to match on. They are
+//! implemented by delegating to the type of the variant chosen with a `static_default = Variant`
+//! argument on the enum. Without one, static methods with a default implementation keep it, while
+//! others are reported as errors. Passing a `kind` argument additionally generates a fieldless
+//! `MyEnumKind` enum with one variant per enum variant, along with an inherent `fn new_for(kind:
+//! MyEnumKind, ...)` method for each static trait method `fn new(...)`, which calls the method on
+//! the selected variant's type.
+//!
+//! The enum then also gets inherent `kind()` and `variant_name()` methods, along with
+//! `VARIANT_NAMES` and `VARIANT_COUNT` constants. `MyEnumKind` has a `name()` method, implements
+//! `Display` using the variant's name, and can be parsed back from that name with `FromStr`,
+//! failing with a generated `ParseMyEnumKindError`, which implements `core::error::Error` and so
+//! requires Rust 1.81. Variants disabled by `#[cfg(...)]` attributes are left out of all of these.
 //!
 //! # How it works
 //!
@@ -328,12 +506,22 @@ extern crate proc_macro;
 use proc_macro2::TokenStream;
 use quote::{ToTokens, TokenStreamExt};
 
+use std::collections::HashMap;
+
 /// Used for converting a macro input into an ItemTrait or an EnumDispatchItem.
 mod attributed_parser;
+/// Provides mirror definitions of well-known traits from the standard library.
+mod builtin_traits;
 /// Provides local storage for enum and trait definitions so that they can be accessed later.
 mod cache;
+/// Provides diagnostics for links that were never fulfilled.
+mod check;
+/// Provides support for linking enums to traits exported from other crates.
+mod cross_crate;
 /// Provides a custom syntax specification for the arguments to an `#[enum_dispatch(...)]` attribute.
 mod enum_dispatch_arg_list;
+/// Provides a custom syntax specification for the contents of `enum_dispatch_block!` invocations.
+mod enum_dispatch_block;
 /// Provides a custom syntax specification for enum dispatch syntax blocks.
 mod enum_dispatch_item;
 /// Provides a custom syntax specification for the variants of enum dispatch syntax blocks.
@@ -342,13 +530,20 @@ mod enum_dispatch_variant;
 mod expansion;
 /// Convenience trait for token parsing.
 mod filter_attrs;
+/// Provides a custom syntax specification for the contents of `foreign_trait!` invocations.
+mod foreign_trait;
 /// Codifies the kinds of generic arguments supported in an `#[enum_dispatch(T<...>)]` attribute.
 mod supported_generics;
 /// Convenience methods for constructing `syn` types.
 mod syn_utils;
 
-use crate::expansion::add_enum_impls;
-use crate::supported_generics::{convert_to_supported_generic, num_supported_generics};
+use crate::enum_dispatch_arg_list::EnumDispatchArg;
+use crate::expansion::{
+    add_conversion_impls, add_enum_impls, add_trait_impl, check_shared_receivers, generate_kind_enum,
+    generate_pin_guards, generate_try_from_error, has_pinned_receivers,
+};
+use crate::supported_generics::{convert_to_supported_generic, num_supported_generics, SupportedGenericArg};
+use crate::syn_utils::{combine_errors, with_arguments};
 
 /// Annotating a trait or enum definition with an `#[enum_dispatch]` attribute will register it
 /// with the enum_dispatch library, allowing it to be used to generate impl blocks elsewhere.
@@ -362,6 +557,9 @@ use crate::supported_generics::{convert_to_supported_generic, num_supported_gene
 /// An annotated enum should have variants that are simply the names of types imported to the
 /// current scope. To force individual variants to use a custom name when expanded, each variant
 /// can also take the form of a normal tuple-style enum variant with a single field.
+///
+/// See the crate-level documentation for the arguments and variant attributes that customize the
+/// generated code.
 #[proc_macro_attribute]
 pub fn enum_dispatch(attr: proc_macro::TokenStream, item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     enum_dispatch2(attr.into(), item.into()).into()
@@ -372,66 +570,197 @@ pub fn enum_dispatch(attr: proc_macro::TokenStream, item: proc_macro::TokenStrea
 /// Using only `proc_macro2::TokenStream` inside the entire crate makes methods unit-testable and
 /// removes the need for conversions everywhere.
 fn enum_dispatch2(attr: TokenStream, item: TokenStream) -> TokenStream {
-    let new_block = attributed_parser::parse_attributed(item.clone()).unwrap();
-    let mut expanded = match &new_block {
-        attributed_parser::ParsedItem::Trait(traitdef) => {
-            cache::cache_trait(traitdef.to_owned());
-            item
-        }
-        attributed_parser::ParsedItem::EnumDispatch(enumdef) => {
-            cache::cache_enum_dispatch(enumdef.clone());
-            syn::ItemEnum::from(enumdef.to_owned())
-                .into_token_stream()
-        }
+    let mut new_block = match attributed_parser::parse_attributed(item) {
+        Ok(new_block) => new_block,
+        Err(e) => return e.to_compile_error(),
     };
+    let is_trait = matches!(new_block, attributed_parser::ParsedItem::Trait(_));
     // If the attributes are non-empty, the new block should be "linked" to the listed definitions.
     // Those definitions may or may not have been cached yet.
     // If one is not cached yet, the link will be pushed into the cache, and impl generation will
     // be deferred until the missing definition is encountered.
     // For now, we assume it is already cached.
-    if !attr.is_empty() {
-        let attr_parse_result = syn::parse2::<enum_dispatch_arg_list::EnumDispatchArgList>(attr)
-            .expect("Could not parse arguments to `#[enum_dispatch(...)]`.")
-            .arg_list
-            .into_iter()
-            .try_for_each(|p| {
-                if p.leading_colon.is_some() || p.segments.len() != 1 {
-                    panic!("Paths in `#[enum_dispatch(...)]` are not supported.");
+    let mut options = vec![];
+    let mut links = vec![];
+    // Problems with the arguments are collected, so that they can all be reported at once.
+    let mut errors = vec![];
+    // Any other `#[enum_dispatch]` attributes on the same item are handled by this expansion, so
+    // that options are merged and per-enum items are only generated once.
+    let mut attr_args = vec![attr];
+    for other in new_block.take_enum_dispatch_attrs() {
+        match other.meta {
+            syn::Meta::Path(_) => (),
+            syn::Meta::List(list) => attr_args.push(list.tokens),
+            syn::Meta::NameValue(meta) => {
+                return syn::Error::new_spanned(meta, "Expected #[enum_dispatch] or #[enum_dispatch(...)]")
+                    .to_compile_error()
+            }
+        }
+    }
+    let mut arg_list = vec![];
+    for attr in attr_args.into_iter().filter(|attr| !attr.is_empty()) {
+        match syn::parse2::<enum_dispatch_arg_list::EnumDispatchArgList>(attr) {
+            Ok(args) => arg_list.extend(args.arg_list),
+            Err(e) => errors.push(e),
+        }
+    }
+    for arg in arg_list {
+        let p = match arg {
+            EnumDispatchArg::Link(p) => p,
+            option @ EnumDispatchArg::Module(..) => {
+                options.push(option);
+                continue;
+            }
+            option @ EnumDispatchArg::Export(..) => {
+                if !is_trait {
+                    errors.push(syn::Error::new_spanned(
+                        &option,
+                        "Only traits can be exported with #[enum_dispatch(export)]",
+                    ));
                 }
-                let syn::PathSegment {
-                    ident: attr_name,
-                    arguments: attr_generics
-                } = p.segments.last().unwrap();
-                let attr_generics = match attr_generics.clone() {
-                    syn::PathArguments::None => vec![],
-                    syn::PathArguments::AngleBracketed(args) => {
-                        assert!(args.colon2_token.is_none());
-                        match args.args.iter().map(convert_to_supported_generic).collect::<Result<Vec<_>, _>>() {
-                            Ok(v) => v,
-                            Err((unsupported, span)) => {
-                                let error_string = unsupported.to_string();
-                                return Err(quote::quote_spanned! {span=>
-                                    compile_error!(#error_string)
-                                });
-                            }
-                        }
+                options.push(option);
+                continue;
+            }
+            option if is_trait => {
+                errors.push(syn::Error::new_spanned(
+                    option,
+                    "This argument to #[enum_dispatch(...)] can only be used on enums",
+                ));
+                continue;
+            }
+            option => {
+                options.push(option);
+                continue;
+            }
+        };
+        let attr_generics = match &p.segments.last().unwrap().arguments {
+            syn::PathArguments::None => vec![],
+            syn::PathArguments::AngleBracketed(args) if args.colon2_token.is_some() => {
+                errors.push(syn::Error::new_spanned(
+                    args.colon2_token,
+                    "Turbofish syntax is not needed in #[enum_dispatch(...)]",
+                ));
+                continue;
+            }
+            syn::PathArguments::AngleBracketed(args) => {
+                match args.args.iter().map(convert_to_supported_generic).collect::<Result<Vec<_>, _>>() {
+                    // Generic arguments given for an enum are only placeholders, so they can't
+                    // be concrete types.
+                    Ok(v) if is_trait && v.iter().any(|arg| matches!(arg, SupportedGenericArg::Type)) => {
+                        errors.push(syn::Error::new_spanned(
+                            args,
+                            "Concrete generic types in #[enum_dispatch(...)] are only supported when linking an enum to a trait",
+                        ));
+                        continue;
                     }
-                    syn::PathArguments::Parenthesized(_) => panic!("Expected angle bracketed generic arguments, found parenthesized arguments"),
-                };
-                match &new_block {
-                    attributed_parser::ParsedItem::Trait(traitdef) => {
-                        let supported_generics = num_supported_generics(&traitdef.generics);
-                        cache::defer_link((attr_name, attr_generics.len()), (&traitdef.ident, supported_generics))
+                    Ok(v) if is_trait && v.iter().any(SupportedGenericArg::is_associated) => {
+                        errors.push(syn::Error::new_spanned(
+                            args,
+                            "Associated type bindings and bounds in #[enum_dispatch(...)] are only supported when linking an enum to a trait",
+                        ));
+                        continue;
                     }
-                    attributed_parser::ParsedItem::EnumDispatch(enumdef) => {
-                        let supported_generics = num_supported_generics(&enumdef.generics);
-                        cache::defer_link((attr_name, attr_generics.len()), (&enumdef.ident, supported_generics))
+                    Ok(v) => v,
+                    Err((unsupported, span)) => {
+                        errors.push(syn::Error::new(span, unsupported.to_string()));
+                        continue;
                     }
                 }
-                Ok(())
-            });
-        if let Err(e) = attr_parse_result {
-            return e;
+            }
+            syn::PathArguments::Parenthesized(args) => {
+                errors.push(syn::Error::new_spanned(
+                    args,
+                    "Expected angle bracketed generic arguments, found parenthesized arguments",
+                ));
+                continue;
+            }
+        };
+        // Lifetimes, associated type bindings and bounds don't identify the linked item.
+        let num_generics = attr_generics.iter().filter(|arg| arg.is_counted()).count();
+        links.push((p, num_generics));
+    }
+    let module = options.iter().rev().find_map(|option| match option {
+        EnumDispatchArg::Module(_, module) => Some(module.clone()),
+        _ => None,
+    });
+    let module = module.as_ref();
+    // Well-known traits from the standard library are implemented directly from their built-in
+    // definitions.
+    let mut builtin_links = vec![];
+    if !is_trait {
+        links.retain(|(path, _)| match builtin_traits::lookup(path) {
+            Some(builtin) => {
+                builtin_links.push(builtin);
+                false
+            }
+            None => true,
+        });
+    }
+    // Links to traits in other crates can't go through the cache. Instead, they are requested from
+    // the trait's crate once the enum has been expanded.
+    let (extern_links, links): (Vec<_>, Vec<_>) = links
+        .into_iter()
+        .partition(|(path, _)| path.leading_colon.is_some());
+    for (path, _) in extern_links.iter() {
+        if is_trait {
+            errors.push(syn::Error::new_spanned(
+                path,
+                "Traits can only be linked to enums defined in the same crate",
+            ));
+        } else if path.segments.len() < 2 {
+            errors.push(syn::Error::new_spanned(
+                path,
+                "Expected a path to a trait in another crate, like `::some_crate::Trait`",
+            ));
+        }
+    }
+    for (path, num_generics) in links.iter() {
+        if let Some(error) = check::check_link_arity(path, *num_generics, is_trait) {
+            errors.push(error);
+        }
+    }
+    if let Some(errors) = combine_errors(errors) {
+        return errors.to_compile_error();
+    }
+    for (path, num_generics) in links.iter() {
+        match &new_block {
+            attributed_parser::ParsedItem::Trait(traitdef) => {
+                let supported_generics = num_supported_generics(&traitdef.generics);
+                cache::defer_link((path, *num_generics), (&traitdef.ident, supported_generics), module)
+            }
+            attributed_parser::ParsedItem::EnumDispatch(enumdef) => {
+                let supported_generics = num_supported_generics(&enumdef.generics);
+                cache::defer_link((path, *num_generics), (&enumdef.ident, supported_generics), module)
+            }
+        }
+    }
+    let mut expanded = match &mut new_block {
+        attributed_parser::ParsedItem::Trait(traitdef) => {
+            cache::cache_trait(traitdef.to_owned(), module);
+            let mut expanded = traitdef.to_token_stream();
+            if let Some(error) = check_shared_receivers(traitdef) {
+                expanded.append_all(error.to_compile_error());
+            }
+            if options.iter().any(|option| matches!(option, EnumDispatchArg::Export(..))) {
+                expanded.append_all(cross_crate::generate_export_macro(traitdef));
+            }
+            expanded
+        }
+        attributed_parser::ParsedItem::EnumDispatch(enumdef) => {
+            enumdef.options = options.clone();
+            cache::cache_enum_dispatch(enumdef.clone(), module);
+            let mut expanded = syn::ItemEnum::from(enumdef.to_owned())
+                .into_token_stream();
+            expanded.append_all(generate_kind_enum(enumdef));
+            expanded.append_all(generate_try_from_error(enumdef));
+            for (path, _) in extern_links.iter() {
+                expanded.append_all(cross_crate::generate_link_request(path, enumdef));
+            }
+            for (traitdef, trait_path) in builtin_links {
+                let enum_path = enumdef.ident.clone().into();
+                expanded.append_all(add_enum_impls(enumdef.clone(), enum_path, traitdef, trait_path));
+            }
+            expanded
         }
     };
     // It would be much simpler to just always retrieve all definitions from the cache. However,
@@ -441,18 +770,215 @@ fn enum_dispatch2(attr: TokenStream, item: TokenStream) -> TokenStream {
         attributed_parser::ParsedItem::Trait(traitdef) => {
             let supported_generics = num_supported_generics(&traitdef.generics);
             let additional_enums =
-                cache::fulfilled_by_trait(&traitdef.ident, supported_generics);
-            for enumdef in additional_enums {
-                expanded.append_all(add_enum_impls(enumdef, traitdef.clone()));
+                cache::fulfilled_by_trait(&traitdef.ident, supported_generics, module);
+            for (enumdef, enum_path, arguments) in additional_enums {
+                let enum_path = enum_path.unwrap_or_else(|| enumdef.ident.clone().into());
+                let trait_path = with_arguments(traitdef.ident.clone().into(), arguments);
+                expanded.append_all(add_enum_impls(enumdef, enum_path, traitdef.clone(), trait_path));
             }
+            expanded.append_all(check::check_definition_arity(&traitdef.ident, supported_generics, module, true));
         }
         attributed_parser::ParsedItem::EnumDispatch(enumdef) => {
             let supported_generics = num_supported_generics(&enumdef.generics);
             let additional_traits =
-                cache::fulfilled_by_enum(&enumdef.ident, supported_generics);
-            for traitdef in additional_traits {
-                expanded.append_all(add_enum_impls(enumdef.clone(), traitdef));
+                cache::fulfilled_by_enum(&enumdef.ident, supported_generics, module);
+            for (traitdef, trait_path, arguments) in additional_traits {
+                let trait_path = trait_path.unwrap_or_else(|| traitdef.ident.clone().into());
+                let enum_path = with_arguments(enumdef.ident.clone().into(), arguments);
+                expanded.append_all(add_enum_impls(enumdef.clone(), enum_path, traitdef, trait_path));
             }
+            expanded.append_all(check::check_definition_arity(&enumdef.ident, supported_generics, module, false));
+        }
+    }
+    expanded
+}
+
+/// Reports links in `#[enum_dispatch(...)]` attributes to traits or enums that were never defined.
+///
+/// Since links can be fulfilled by items defined anywhere in the crate, a link to a misspelled or
+/// unannotated item is silently ignored, leaving its impls missing. Invoking this macro at the end
+/// of the crate root, after all modules, generates an error for each link that is still unresolved
+/// at that point, including the expected number of generic arguments and a suggestion for the
+/// intended item.
+///
+/// ```
+/// # use enum_dispatch::enum_dispatch;
+/// # struct LinearKnob;
+/// # impl KnobControl for LinearKnob {}
+/// #[enum_dispatch(Knob)]
+/// trait KnobControl {}
+///
+/// #[enum_dispatch]
+/// enum Knob {
+///     LinearKnob,
+/// }
+///
+/// enum_dispatch::check!();
+/// # fn main() {}
+/// ```
+#[proc_macro]
+pub fn check(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = TokenStream::from(input);
+    if !input.is_empty() {
+        return syn::Error::new_spanned(input, "`check!()` does not take any arguments")
+            .to_compile_error()
+            .into();
+    }
+    check::check_links().into()
+}
+
+/// Registers mirrors of traits defined in other crates, so that `enum_dispatch` enums can
+/// implement them.
+///
+/// Foreign traits can't be annotated with `#[enum_dispatch]`. Instead, their signatures can be
+/// declared once with this macro, using the path to the real trait in place of its name. Enums
+/// link to the trait by name as usual, and generated impls will refer to the trait by its real
+/// path. The mirror itself doesn't generate any code, so any mismatches between the mirror and the
+/// real trait will be reported by the compiler in the generated impls.
+///
+/// ```
+/// # mod some_crate {
+/// #     pub trait Handler {
+/// #         fn handle(&self, request: u32) -> u32;
+/// #     }
+/// # }
+/// # use enum_dispatch::{enum_dispatch, foreign_trait};
+/// # struct Doubler;
+/// # impl some_crate::Handler for Doubler {
+/// #     fn handle(&self, request: u32) -> u32 { request * 2 }
+/// # }
+/// foreign_trait! {
+///     trait some_crate::Handler {
+///         fn handle(&self, request: u32) -> u32;
+///     }
+/// }
+///
+/// #[enum_dispatch(Handler)]
+/// enum AnyHandler {
+///     Doubler,
+/// }
+/// # use some_crate::Handler as _;
+/// # let handler: AnyHandler = Doubler.into();
+/// # assert_eq!(handler.handle(2), 4);
+/// ```
+#[proc_macro]
+pub fn foreign_trait(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    foreign_trait2(input.into()).into()
+}
+
+/// `proc_macro2::TokenStream` compatible version of the `foreign_trait` function.
+fn foreign_trait2(input: TokenStream) -> TokenStream {
+    let list = match syn::parse2::<foreign_trait::ForeignTraitList>(input) {
+        Ok(list) => list,
+        Err(e) => return e.to_compile_error(),
+    };
+    let mut expanded = TokenStream::new();
+    for foreign_trait::ForeignTrait { path, traitdef } in list.traits {
+        if let Some(error) = check_shared_receivers(&traitdef) {
+            expanded.append_all(error.to_compile_error());
+        }
+        cache::cache_foreign_trait(traitdef.clone(), &path);
+        let supported_generics = num_supported_generics(&traitdef.generics);
+        let additional_enums = cache::fulfilled_by_trait(&traitdef.ident, supported_generics, None);
+        for (enumdef, enum_path, arguments) in additional_enums {
+            let enum_path = enum_path.unwrap_or_else(|| enumdef.ident.clone().into());
+            let path = with_arguments(path.clone(), arguments);
+            expanded.append_all(add_enum_impls(enumdef, enum_path, traitdef.clone(), path));
+        }
+        expanded.append_all(check::check_definition_arity(&traitdef.ident, supported_generics, None, true));
+    }
+    expanded
+}
+
+/// Implementation detail of linking enums to traits exported from other crates.
+///
+/// This is invoked by the macro generated for a trait annotated with `#[enum_dispatch(export)]`,
+/// with the path to the trait, the definition of the enum to link, and the definition of the
+/// trait. It should not be used directly.
+#[doc(hidden)]
+#[proc_macro]
+pub fn __enum_dispatch_link(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    enum_dispatch_link2(input.into()).into()
+}
+
+/// `proc_macro2::TokenStream` compatible version of the `__enum_dispatch_link` function.
+fn enum_dispatch_link2(input: TokenStream) -> TokenStream {
+    let callback = match syn::parse2::<cross_crate::LinkCallback>(input) {
+        Ok(callback) => callback,
+        Err(e) => return e.to_compile_error(),
+    };
+    let enumdef = callback.enumdef;
+    let enum_path = enumdef.ident.clone().into();
+    add_enum_impls(enumdef, enum_path, callback.traitdef, callback.trait_path)
+}
+
+/// Defines traits and `enum_dispatch` enums together in a single invocation, implementing every
+/// trait in the block for every enum in the block.
+///
+/// Unlike the `#[enum_dispatch]` attribute, this macro does not depend on any state shared between
+/// macro invocations, so its expansion is unaffected by the order in which items are expanded or
+/// by the lifetime of the compiler process (e.g. when used with incremental builds or IDEs). The
+/// tradeoff is that all linked items must be defined in the same place.
+///
+/// ```
+/// # use enum_dispatch::enum_dispatch_block;
+/// # struct LinearKnob { position: f64 }
+/// # impl KnobControl for LinearKnob {
+/// #     fn get_value(&self) -> f64 { self.position }
+/// # }
+/// enum_dispatch_block! {
+///     trait KnobControl {
+///         fn get_value(&self) -> f64;
+///     }
+///
+///     enum Knob {
+///         LinearKnob,
+///     }
+/// }
+/// # let knob: Knob = LinearKnob { position: 0.5 }.into();
+/// # assert_eq!(knob.get_value(), 0.5);
+/// ```
+///
+/// Non-link arguments, like `kind` or values for associated items, can be passed to an enum in the
+/// block using an `#[enum_dispatch(...)]` attribute on the enum.
+#[proc_macro]
+pub fn enum_dispatch_block(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    enum_dispatch_block2(input.into()).into()
+}
+
+/// `proc_macro2::TokenStream` compatible version of the `enum_dispatch_block` function.
+fn enum_dispatch_block2(input: TokenStream) -> TokenStream {
+    let block = match syn::parse2::<enum_dispatch_block::EnumDispatchBlock>(input) {
+        Ok(block) => block,
+        Err(e) => return e.to_compile_error(),
+    };
+    let mut expanded = TokenStream::new();
+    for traitdef in block.traits.iter() {
+        traitdef.to_tokens(&mut expanded);
+        if let Some(error) = check_shared_receivers(traitdef) {
+            expanded.append_all(error.to_compile_error());
+        }
+    }
+    for enumdef in block.enums.iter() {
+        syn::ItemEnum::from(enumdef.to_owned()).to_tokens(&mut expanded);
+        expanded.append_all(generate_kind_enum(enumdef));
+        expanded.append_all(generate_try_from_error(enumdef));
+
+        let enum_path = enumdef.ident.clone().into();
+        expanded.append_all(add_conversion_impls(enumdef, &enum_path));
+        if block.traits.iter().any(has_pinned_receivers) {
+            expanded.append_all(generate_pin_guards(enumdef, &enum_path));
+        }
+        let mut const_accessors = HashMap::new();
+        for traitdef in block.traits.iter() {
+            let trait_path = traitdef.ident.clone().into();
+            expanded.append_all(add_trait_impl(
+                enumdef,
+                &enum_path,
+                traitdef.clone(),
+                trait_path,
+                &mut const_accessors,
+            ));
         }
     }
     expanded
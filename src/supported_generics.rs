@@ -6,23 +6,39 @@ pub enum SupportedGenericArg {
     /// A `_` type.
     Inferred,
     /// A named generic argument, e.g. `T`.
-    Identifier(proc_macro2::Ident),
-    /// A const generic char, e.g. `'a'`.
-    ConstChar(syn::LitChar),
-    /// A const generic byte, e.g. `b'a'`.
-    ConstByte(syn::LitByte),
-    /// A const generic integer, e.g. `9`.
-    ConstInt(syn::LitInt),
-    /// A const generic integer, e.g. `true`.
-    ConstBool(syn::LitBool),
+    Identifier,
+    /// A concrete type argument, e.g. `Vec<u8>`. Only supported in links to traits.
+    Type,
+    /// An associated type binding, e.g. `Item = u8`. Only supported in links to traits.
+    AssocType,
+    /// Bounds on an associated type, e.g. `Item: Clone`. Only supported in links to traits.
+    Constraint,
+    /// A lifetime argument, e.g. `'a`.
+    Lifetime,
+    /// A const generic expression, e.g. `9`, `'a'` or `{ LEN * 2 }`. Const arguments that are
+    /// just a name or path, like `LEN`, can't be told apart from types, so they are parsed as
+    /// `Identifier` or `Type` instead.
+    Const,
+}
+
+impl SupportedGenericArg {
+    /// Returns true if this is an associated type binding or constraint, rather than an argument
+    /// for one of the generic parameters of the item.
+    pub fn is_associated(&self) -> bool {
+        matches!(self, Self::AssocType | Self::Constraint)
+    }
+
+    /// Returns true if this argument counts towards the number of generic arguments used to
+    /// identify the linked item, like the parameters counted by `num_supported_generics`.
+    pub fn is_counted(&self) -> bool {
+        !self.is_associated() && !matches!(self, Self::Lifetime)
+    }
 }
 
 /// Represents any single generic argument from `#[enum_dispatch(Ty<...>)]` that can _not_ be
 /// supported by `enum_dispatch`.
 pub enum UnsupportedGenericArg {
-    NonIdentifierType,
     NonIntegralConstGenericType,
-    Lifetime,
     Constraint,
     AssocType,
     AssocConst,
@@ -32,9 +48,7 @@ pub enum UnsupportedGenericArg {
 impl std::fmt::Display for UnsupportedGenericArg {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::NonIdentifierType => write!(f, "Generic types in #[enum_dispatch(...)] must be identifiers"),
             Self::NonIntegralConstGenericType => write!(f, "Non-integral const generic types in #[enum_dispatch(...)] are not supported"),
-            Self::Lifetime => write!(f, "Lifetime generics in #[enum_dispatch(...)] are not supported"),
             Self::AssocType => write!(f, "Generic associated types in #[enum_dispatch(...)] are not supported"),
             Self::AssocConst => write!(f, "Generic associated constants in #[enum_dispatch(...)] are not supported"),
             Self::Constraint => write!(f, "Generic trait constraints in #[enum_dispatch(...)] are not supported"),
@@ -43,39 +57,13 @@ impl std::fmt::Display for UnsupportedGenericArg {
     }
 }
 
-/// Strings corresponding to types that are supported as const generics.
-const SUPPORTED_CONST_GENERIC_TYPES: &[&str] = &[
-    "u8",
-    "i8",
-    "u16",
-    "i16",
-    "u32",
-    "i32",
-    "u64",
-    "i64",
-    "u128",
-    "i128",
-    "usize",
-    "isize",
-    "char",
-    "bool",
-];
-
 /// Counts the number of supported generic parameters from an enum or trait definition.
+///
+/// Lifetime parameters aren't counted, since links can leave out their arguments. The types of
+/// const generic parameters aren't checked, since they may be given through aliases like
+/// `const N: Size` that can't be resolved here.
 pub fn num_supported_generics(g: &syn::Generics) -> usize {
-    let type_generics = g.type_params().count();
-    let const_generics = g.const_params().filter(|p| {
-        if let syn::Type::Path(syn::TypePath { qself: None, path }) = &p.ty {
-            for supported_type in SUPPORTED_CONST_GENERIC_TYPES {
-                if path.is_ident(supported_type) {
-                    return true;
-                }
-            }
-        }
-        false
-    }).count();
-
-    type_generics + const_generics
+    g.type_params().count() + g.const_params().count()
 }
 
 /// Converts a `syn::GenericArgument` to a `SupportedGenericArg`, or an `UnsupportedGenericArg` if
@@ -86,26 +74,27 @@ pub fn convert_to_supported_generic(generic_arg: &syn::GenericArgument) -> Resul
 
     match generic_arg {
         syn::GenericArgument::Type(syn::Type::Path(t)) if t.qself.is_none() => {
-            if let Some(ident) = t.path.get_ident() {
-                Ok(SupportedGenericArg::Identifier(ident.clone()))
+            if t.path.get_ident().is_some() {
+                Ok(SupportedGenericArg::Identifier)
             } else {
-                Err((UnsupportedGenericArg::NonIdentifierType, span))
+                Ok(SupportedGenericArg::Type)
             }
         }
         syn::GenericArgument::Type(syn::Type::Infer(_)) => Ok(SupportedGenericArg::Inferred),
-        syn::GenericArgument::Type(_) => Err((UnsupportedGenericArg::NonIdentifierType, span)),
+        syn::GenericArgument::Type(_) => Ok(SupportedGenericArg::Type),
         syn::GenericArgument::Const(syn::Expr::Lit(syn::ExprLit { attrs: _, lit })) => {
             match lit {
-                syn::Lit::Byte(b) => Ok(SupportedGenericArg::ConstByte(b.clone())),
-                syn::Lit::Char(c) => Ok(SupportedGenericArg::ConstChar(c.clone())),
-                syn::Lit::Int(i) => Ok(SupportedGenericArg::ConstInt(i.clone())),
-                syn::Lit::Bool(b) => Ok(SupportedGenericArg::ConstBool(b.clone())),
+                syn::Lit::Byte(_) | syn::Lit::Char(_) | syn::Lit::Int(_) | syn::Lit::Bool(_) => {
+                    Ok(SupportedGenericArg::Const)
+                }
                 _ => Err((UnsupportedGenericArg::NonIntegralConstGenericType, span)),
             }
         }
-        syn::GenericArgument::Const(_) => Err((UnsupportedGenericArg::NonIntegralConstGenericType, span)),
-        syn::GenericArgument::Lifetime(_) => Err((UnsupportedGenericArg::Lifetime, span)),
+        syn::GenericArgument::Const(_) => Ok(SupportedGenericArg::Const),
+        syn::GenericArgument::Lifetime(_) => Ok(SupportedGenericArg::Lifetime),
+        syn::GenericArgument::Constraint(c) if c.generics.is_none() => Ok(SupportedGenericArg::Constraint),
         syn::GenericArgument::Constraint(_) => Err((UnsupportedGenericArg::Constraint, span)),
+        syn::GenericArgument::AssocType(a) if a.generics.is_none() => Ok(SupportedGenericArg::AssocType),
         syn::GenericArgument::AssocType(_) => Err((UnsupportedGenericArg::AssocType, span)),
         syn::GenericArgument::AssocConst(_) => Err((UnsupportedGenericArg::AssocConst, span)),
         _ => Err((UnsupportedGenericArg::Unknown, span)),
@@ -18,3 +18,37 @@ pub fn plain_identifier_expr(ident: Ident) -> Expr {
         },
     })
 }
+
+/// Combines a list of errors into a single error that reports all of them, if there are any.
+pub fn combine_errors(errors: impl IntoIterator<Item = syn::Error>) -> Option<syn::Error> {
+    errors.into_iter().reduce(|mut combined, error| {
+        combined.combine(error);
+        combined
+    })
+}
+
+/// Converts a `CamelCase` identifier into `snake_case`, keeping acronyms together, e.g.
+/// `HTTPHandler` becomes `http_handler`.
+pub fn snake_case(ident: &Ident) -> String {
+    let name = ident.to_string();
+    let name = name.trim_start_matches("r#");
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut snake = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            if prev != '_' && (prev.is_lowercase() || prev.is_ascii_digit() || (prev.is_uppercase() && next_is_lower)) {
+                snake.push('_');
+            }
+        }
+        snake.extend(c.to_lowercase());
+    }
+    snake
+}
+
+/// Replaces the generic arguments of the final segment of a path.
+pub fn with_arguments(mut path: Path, arguments: PathArguments) -> Path {
+    path.segments.last_mut().unwrap().arguments = arguments;
+    path
+}
@@ -0,0 +1,118 @@
+use enum_dispatch::enum_dispatch;
+use std::convert::TryFrom;
+
+#[enum_dispatch]
+trait Knob {
+    fn get_value(&self) -> f64;
+    fn set_value(&mut self, value: f64);
+}
+
+#[derive(Debug, PartialEq)]
+struct LinearKnob {
+    position: f64,
+}
+
+#[derive(Debug, PartialEq)]
+struct HTTPKnob {
+    position: f64,
+}
+
+impl Knob for LinearKnob {
+    fn get_value(&self) -> f64 {
+        self.position
+    }
+
+    fn set_value(&mut self, value: f64) {
+        self.position = value;
+    }
+}
+
+impl Knob for HTTPKnob {
+    fn get_value(&self) -> f64 {
+        self.position * 2.0
+    }
+
+    fn set_value(&mut self, value: f64) {
+        self.position = value / 2.0;
+    }
+}
+
+#[enum_dispatch(Knob, accessors)]
+enum AnyKnob {
+    LinearKnob,
+    HTTPKnob,
+}
+
+// Without `accessors`, no inherent methods are generated that could collide with the enum's own.
+#[enum_dispatch(Knob)]
+enum Selection {
+    Variant(LinearKnob),
+}
+
+impl Selection {
+    fn is_variant(&self) -> bool {
+        true
+    }
+
+    fn as_variant(&self) -> &LinearKnob {
+        match self {
+            Selection::Variant(knob) => knob,
+        }
+    }
+}
+
+#[test]
+fn variant_accessors() {
+    let mut knob: AnyKnob = LinearKnob { position: 1.0 }.into();
+
+    assert!(knob.is_linear_knob());
+    assert!(!knob.is_http_knob());
+    assert_eq!(knob.as_linear_knob(), Some(&LinearKnob { position: 1.0 }));
+    assert_eq!(knob.as_http_knob(), None);
+
+    knob.as_linear_knob_mut().unwrap().position = 3.0;
+    assert_eq!(knob.get_value(), 3.0);
+    assert!(knob.as_http_knob_mut().is_none());
+}
+
+#[test]
+fn generic_accessors() {
+    let mut knob: AnyKnob = HTTPKnob { position: 1.0 }.into();
+
+    assert!(knob.is_variant::<HTTPKnob>());
+    assert!(!knob.is_variant::<LinearKnob>());
+    assert_eq!(knob.variant::<HTTPKnob>(), Some(&HTTPKnob { position: 1.0 }));
+    assert_eq!(knob.variant::<LinearKnob>(), None);
+
+    knob.variant_mut::<HTTPKnob>().unwrap().position = 2.0;
+    assert_eq!(knob.get_value(), 4.0);
+
+    assert_eq!(
+        knob.into_variant::<HTTPKnob>().ok(),
+        Some(HTTPKnob { position: 2.0 })
+    );
+}
+
+#[test]
+fn try_from_references() {
+    let mut knob: AnyKnob = LinearKnob { position: 1.0 }.into();
+
+    assert_eq!(
+        <&LinearKnob>::try_from(&knob),
+        Ok(&LinearKnob { position: 1.0 })
+    );
+    assert_eq!(
+        <&HTTPKnob>::try_from(&knob),
+        Err("Tried to convert variant LinearKnob to HTTPKnob")
+    );
+
+    <&mut LinearKnob>::try_from(&mut knob).unwrap().set_value(5.0);
+    assert_eq!(knob.get_value(), 5.0);
+}
+
+#[test]
+fn own_methods_without_accessors() {
+    let selection: Selection = LinearKnob { position: 2.0 }.into();
+    assert!(selection.is_variant());
+    assert_eq!(selection.as_variant().get_value(), 2.0);
+}
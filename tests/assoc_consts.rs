@@ -0,0 +1,71 @@
+use enum_dispatch::enum_dispatch;
+
+#[enum_dispatch]
+trait Plugin {
+    const NAME: &'static str;
+    const PRIORITY: u8 = 0;
+
+    fn run(&self) -> u8;
+}
+
+struct Logger;
+struct Tracer;
+
+impl Plugin for Logger {
+    const NAME: &'static str = "logger";
+
+    fn run(&self) -> u8 {
+        1
+    }
+}
+
+impl Plugin for Tracer {
+    const NAME: &'static str = "tracer";
+    const PRIORITY: u8 = 5;
+
+    fn run(&self) -> u8 {
+        2
+    }
+}
+
+#[enum_dispatch(Plugin, NAME = "any plugin", const_accessors)]
+enum AnyPlugin {
+    Logger,
+    Tracer,
+}
+
+#[test]
+fn main() {
+    let logger: AnyPlugin = Logger.into();
+    let tracer: AnyPlugin = Tracer.into();
+
+    assert_eq!(<AnyPlugin as Plugin>::NAME, "any plugin");
+    assert_eq!(<AnyPlugin as Plugin>::PRIORITY, 0);
+
+    assert_eq!(logger.name(), "logger");
+    assert_eq!(tracer.name(), "tracer");
+    assert_eq!(logger.priority(), 0);
+    assert_eq!(tracer.priority(), 5);
+    assert_eq!(tracer.run(), 2);
+}
+
+#[enum_dispatch]
+trait Versioned {
+    const NAME: &'static str = "versioned";
+}
+
+impl Versioned for Logger {}
+impl Versioned for Tracer {}
+
+// Without `const_accessors`, traits may share constant names.
+#[enum_dispatch(Plugin, Versioned, NAME = "any plugin")]
+enum VersionedPlugin {
+    Logger,
+    Tracer,
+}
+
+#[test]
+fn shared_constant_names() {
+    assert_eq!(<VersionedPlugin as Plugin>::NAME, "any plugin");
+    assert_eq!(<VersionedPlugin as Versioned>::NAME, "any plugin");
+}
@@ -0,0 +1,125 @@
+use enum_dispatch::enum_dispatch;
+
+#[enum_dispatch]
+trait Parser {
+    type Output: std::fmt::Debug + PartialEq;
+
+    fn parse(&self, input: &str) -> Self::Output;
+    fn name(&self) -> &'static str;
+}
+
+struct IntParser;
+struct BoolParser;
+
+impl Parser for IntParser {
+    type Output = i64;
+
+    fn parse(&self, input: &str) -> i64 {
+        input.parse().unwrap()
+    }
+
+    fn name(&self) -> &'static str {
+        "int"
+    }
+}
+
+impl Parser for BoolParser {
+    type Output = bool;
+
+    fn parse(&self, input: &str) -> bool {
+        input == "true"
+    }
+
+    fn name(&self) -> &'static str {
+        "bool"
+    }
+}
+
+#[enum_dispatch(Parser)]
+enum AnyParser {
+    IntParser,
+    BoolParser,
+}
+
+#[enum_dispatch]
+trait Codec {
+    type Output;
+
+    fn decode(&self, byte: u8) -> Self::Output;
+}
+
+impl Codec for IntParser {
+    type Output = u32;
+
+    fn decode(&self, byte: u8) -> u32 {
+        byte as u32
+    }
+}
+
+impl Codec for BoolParser {
+    type Output = u32;
+
+    fn decode(&self, byte: u8) -> u32 {
+        (byte != 0) as u32
+    }
+}
+
+#[enum_dispatch(Codec, type Output = u32)]
+enum AnyCodec {
+    IntParser,
+    BoolParser,
+}
+
+#[test]
+fn main() {
+    let int: AnyParser = IntParser.into();
+    let boolean: AnyParser = BoolParser.into();
+
+    assert_eq!(int.parse("42"), AnyParserParserOutput::IntParser(42));
+    assert_eq!(boolean.parse("true"), AnyParserParserOutput::BoolParser(true));
+    assert_eq!(boolean.name(), "bool");
+
+    let codec: AnyCodec = BoolParser.into();
+    let decoded: u32 = codec.decode(7);
+    assert_eq!(decoded, 1);
+}
+
+#[enum_dispatch]
+trait Render {
+    type Output: std::fmt::Debug + PartialEq;
+
+    fn render(&self) -> Self::Output;
+}
+
+impl Render for IntParser {
+    type Output = String;
+
+    fn render(&self) -> String {
+        "int".to_string()
+    }
+}
+
+impl Render for BoolParser {
+    type Output = char;
+
+    fn render(&self) -> char {
+        'b'
+    }
+}
+
+// Each trait gets its own output enum, even though both associated types are named `Output`.
+#[enum_dispatch(Parser, Render)]
+enum AnyFormat {
+    IntParser,
+    BoolParser,
+}
+
+#[test]
+fn same_associated_type_names() {
+    let int: AnyFormat = IntParser.into();
+    let boolean: AnyFormat = BoolParser.into();
+
+    assert_eq!(int.parse("7"), AnyFormatParserOutput::IntParser(7));
+    assert_eq!(int.render(), AnyFormatRenderOutput::IntParser("int".to_string()));
+    assert_eq!(boolean.render(), AnyFormatRenderOutput::BoolParser('b'));
+}
@@ -0,0 +1,108 @@
+use enum_dispatch::enum_dispatch;
+
+pub struct Countdown(u8);
+
+impl Iterator for Countdown {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        self.0 = self.0.checked_sub(1)?;
+        Some(self.0)
+    }
+}
+
+pub struct Repeat(u8, usize);
+
+impl Iterator for Repeat {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        self.1 = self.1.checked_sub(1)?;
+        Some(self.0)
+    }
+}
+
+#[enum_dispatch(iter::Iterator<Item = u8>)]
+enum Digits {
+    Countdown,
+    Repeat,
+}
+
+#[enum_dispatch]
+trait Service<Req> {
+    type Response;
+
+    fn call(&mut self, request: Req) -> Self::Response;
+}
+
+pub struct Echo;
+pub struct Length;
+
+impl Service<String> for Echo {
+    type Response = String;
+
+    fn call(&mut self, request: String) -> String {
+        request
+    }
+}
+
+impl Service<String> for Length {
+    type Response = String;
+
+    fn call(&mut self, request: String) -> String {
+        request.len().to_string()
+    }
+}
+
+#[enum_dispatch(Service<String, Response = String>)]
+enum AnyService {
+    Echo,
+    Length,
+}
+
+pub struct Wrapped<I>(I);
+
+impl<I: Iterator> Iterator for Wrapped<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        self.0.next()
+    }
+}
+
+// The bound is required of the item type of every variant, including generic ones.
+#[enum_dispatch(iter::Iterator<Item: Copy>)]
+enum Tagged<I: Iterator> {
+    Countdown,
+    Wrapped(Wrapped<I>),
+}
+
+#[test]
+fn iterator_item_binding() {
+    let countdown: Digits = Countdown(3).into();
+    let repeat: Digits = Repeat(7, 2).into();
+
+    let digits: Vec<u8> = countdown.chain(repeat).collect();
+    assert_eq!(digits, vec![2, 1, 0, 7, 7]);
+}
+
+#[test]
+fn service_response_binding() {
+    let mut echo: AnyService = Echo.into();
+    let mut length: AnyService = Length.into();
+
+    let responses: Vec<String> = vec![
+        echo.call("hello".to_string()),
+        length.call("hello".to_string()),
+    ];
+    assert_eq!(responses, vec!["hello", "5"]);
+}
+
+#[test]
+fn item_bound() {
+    let mut wrapped: Tagged<std::vec::IntoIter<char>> = Wrapped(vec!['a'].into_iter()).into();
+    assert!(matches!(wrapped.next(), Some(TaggedIteratorItem::Wrapped('a'))));
+
+    let mut countdown: Tagged<std::vec::IntoIter<char>> = Countdown(1).into();
+    assert!(matches!(countdown.next(), Some(TaggedIteratorItem::Countdown(0))));
+}
@@ -0,0 +1,108 @@
+use enum_dispatch::enum_dispatch;
+
+#[derive(Debug, PartialEq)]
+pub struct Point(f64, f64);
+
+#[derive(Debug, PartialEq)]
+pub struct Interval(f64);
+
+#[enum_dispatch]
+trait Geometry {
+    fn distance(&self, other: &Self) -> f64;
+    fn merge(&mut self, other: Self);
+    fn same(&self, other: &Self) -> bool;
+    fn combine(a: Self, b: Self) -> Self;
+}
+
+impl Geometry for Point {
+    fn distance(&self, other: &Self) -> f64 {
+        ((self.0 - other.0).powi(2) + (self.1 - other.1).powi(2)).sqrt()
+    }
+    fn merge(&mut self, other: Self) {
+        self.0 = self.0.max(other.0);
+        self.1 = self.1.max(other.1);
+    }
+    fn same(&self, other: &Self) -> bool {
+        self == other
+    }
+    fn combine(a: Self, b: Self) -> Self {
+        Point(a.0 + b.0, a.1 + b.1)
+    }
+}
+
+impl Geometry for Interval {
+    fn distance(&self, other: &Self) -> f64 {
+        (self.0 - other.0).abs()
+    }
+    fn merge(&mut self, other: Self) {
+        self.0 += other.0;
+    }
+    fn same(&self, other: &Self) -> bool {
+        self == other
+    }
+    fn combine(a: Self, b: Self) -> Self {
+        Interval(a.0 + b.0)
+    }
+}
+
+fn far_apart(_a: &Shape, _b: &Shape) -> f64 {
+    f64::INFINITY
+}
+
+fn never_same(_a: &Shape, _b: &Shape) -> bool {
+    false
+}
+
+#[enum_dispatch(
+    Geometry,
+    mismatch = "cannot mix points and intervals",
+    mismatch(distance) = far_apart,
+    mismatch(same) = never_same,
+)]
+#[derive(Debug, PartialEq)]
+enum Shape {
+    Point,
+    Interval,
+}
+
+#[test]
+fn matching_variants() {
+    let a: Shape = Point(0.0, 0.0).into();
+    let b: Shape = Point(3.0, 4.0).into();
+    assert_eq!(a.distance(&b), 5.0);
+    assert!(a.same(&Point(0.0, 0.0).into()));
+
+    let mut c: Shape = Interval(1.0).into();
+    c.merge(Interval(2.5).into());
+    assert_eq!(c, Shape::Interval(Interval(3.5)));
+
+    let combined = <Shape as Geometry>::combine(a, b);
+    assert_eq!(combined, Shape::Point(Point(3.0, 4.0)));
+}
+
+#[test]
+fn mismatched_variants_use_fallback() {
+    let a: Shape = Point(0.0, 0.0).into();
+    let b: Shape = Interval(1.0).into();
+    assert_eq!(a.distance(&b), f64::INFINITY);
+    assert!(!a.same(&b));
+}
+
+#[test]
+#[should_panic(expected = "cannot mix points and intervals")]
+fn mismatched_variants_panic() {
+    let mut a: Shape = Point(0.0, 0.0).into();
+    a.merge(Interval(1.0).into());
+}
+
+#[enum_dispatch(Geometry)]
+enum OnlyPoints {
+    Point,
+}
+
+#[test]
+fn single_variant() {
+    let a: OnlyPoints = Point(1.0, 1.0).into();
+    let b: OnlyPoints = Point(1.0, 1.0).into();
+    assert!(a.same(&b));
+}
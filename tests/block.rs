@@ -0,0 +1,56 @@
+use enum_dispatch::enum_dispatch_block;
+
+struct Square(f64);
+struct Circle(f64);
+
+impl Shape for Square {
+    fn area(&self) -> f64 {
+        self.0 * self.0
+    }
+}
+
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        3. * self.0 * self.0
+    }
+}
+
+impl Named for Square {
+    const NAME: &'static str = "square";
+}
+
+impl Named for Circle {
+    const NAME: &'static str = "circle";
+}
+
+enum_dispatch_block! {
+    trait Shape {
+        fn area(&self) -> f64;
+    }
+
+    trait Named {
+        const NAME: &'static str;
+    };
+
+    #[enum_dispatch(NAME = "shape", const_accessors)]
+    enum AnyShape {
+        Square,
+        Circle,
+    }
+}
+
+#[test]
+fn main() {
+    use core::convert::TryInto;
+
+    let square: AnyShape = Square(2.).into();
+    let circle: AnyShape = Circle(1.).into();
+
+    assert_eq!(square.area(), 4.);
+    assert_eq!(circle.area(), 3.);
+    assert_eq!(circle.name(), "circle");
+    assert_eq!(<AnyShape as Named>::NAME, "shape");
+
+    let circle: Result<Circle, _> = circle.try_into();
+    assert!(circle.is_ok());
+}
@@ -0,0 +1,217 @@
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use enum_dispatch::enum_dispatch;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::io::{Read, Seek, SeekFrom, Write};
+
+#[derive(Debug)]
+struct NotFound(String);
+
+impl fmt::Display for NotFound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} not found", self.0)
+    }
+}
+
+impl std::error::Error for NotFound {}
+
+#[derive(Debug)]
+struct Wrapped(std::io::Error);
+
+impl fmt::Display for Wrapped {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "wrapped")
+    }
+}
+
+impl std::error::Error for Wrapped {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+#[enum_dispatch(fmt::Display, std::fmt::Debug, error::Error)]
+enum AppError {
+    NotFound,
+    Wrapped,
+}
+
+#[test]
+fn errors() {
+    let not_found: AppError = NotFound("page".to_string()).into();
+    let wrapped: AppError = Wrapped(std::io::Error::other("disk")).into();
+    assert_eq!(not_found.to_string(), "page not found");
+    assert_eq!(format!("{:?}", not_found), "NotFound(\"page\")");
+    assert!(std::error::Error::source(&not_found).is_none());
+    assert_eq!(std::error::Error::source(&wrapped).unwrap().to_string(), "disk");
+}
+
+#[derive(Hash)]
+struct Countdown(u8);
+
+impl Iterator for Countdown {
+    type Item = u8;
+    fn next(&mut self) -> Option<u8> {
+        self.0 = self.0.checked_sub(1)?;
+        Some(self.0)
+    }
+}
+
+impl DoubleEndedIterator for Countdown {
+    fn next_back(&mut self) -> Option<u8> {
+        self.next()
+    }
+}
+
+#[derive(Hash)]
+struct Letters(Vec<char>);
+
+impl Iterator for Letters {
+    type Item = char;
+    fn next(&mut self) -> Option<char> {
+        self.0.pop()
+    }
+}
+
+#[enum_dispatch(iter::Iterator, type Item = u8, iter::DoubleEndedIterator, hash::Hash)]
+enum Numbers {
+    Countdown,
+    Range(std::ops::Range<u8>),
+}
+
+#[enum_dispatch(iter::Iterator)]
+enum Sequence {
+    Countdown,
+    Letters,
+}
+
+#[test]
+fn iterators() {
+    let numbers: Numbers = Countdown(3).into();
+    assert_eq!(numbers.collect::<Vec<_>>(), vec![2, 1, 0]);
+    let numbers: Numbers = (1..4).into();
+    assert_eq!(numbers.rev().collect::<Vec<_>>(), vec![3, 2, 1]);
+
+    let mut sequence: Sequence = Letters(vec!['b', 'a']).into();
+    assert!(matches!(sequence.next(), Some(SequenceIteratorItem::Letters('a'))));
+    let mut sequence: Sequence = Countdown(1).into();
+    assert!(matches!(sequence.next(), Some(SequenceIteratorItem::Countdown(0))));
+    assert!(sequence.next().is_none());
+}
+
+#[test]
+fn hash() {
+    fn hash_of<T: Hash>(value: T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+    let numbers: Numbers = Countdown(7).into();
+    assert_eq!(hash_of(numbers), hash_of(Countdown(7)));
+}
+
+#[enum_dispatch(io::Read, io::Write, io::Seek)]
+enum Stream {
+    Cursor(std::io::Cursor<Vec<u8>>),
+    Empty(std::io::Empty),
+}
+
+#[test]
+fn io() {
+    let mut stream: Stream = std::io::Cursor::new(vec![]).into();
+    let name = "world";
+    write!(stream, "hello {}", name).unwrap();
+    stream.flush().unwrap();
+    stream.seek(SeekFrom::Start(6)).unwrap();
+    let mut contents = String::new();
+    stream.read_to_string(&mut contents).unwrap();
+    assert_eq!(contents, "world");
+
+    let mut stream: Stream = std::io::empty().into();
+    assert_eq!(stream.write(b"discarded").unwrap(), 9);
+    assert_eq!(stream.read(&mut [0; 4]).unwrap(), 0);
+}
+
+#[enum_dispatch(fmt::Write)]
+enum Output {
+    String,
+}
+
+#[test]
+fn fmt_write() {
+    use std::fmt::Write as _;
+    let mut output: Output = String::new().into();
+    write!(output, "{}-{}", 1, 2).unwrap();
+    output.write_char('!').unwrap();
+    let Output::String(output) = output;
+    assert_eq!(output, "1-2!");
+}
+
+struct Ready(u32);
+
+impl std::future::Future for Ready {
+    type Output = u32;
+    fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<u32> {
+        Poll::Ready(self.0)
+    }
+}
+
+struct Doubled(Ready);
+
+impl std::future::Future for Doubled {
+    type Output = u64;
+    fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<u64> {
+        Poll::Ready(self.0 .0 as u64 * 2)
+    }
+}
+
+#[enum_dispatch(future::Future, type Output = u32)]
+enum Task {
+    Ready,
+}
+
+#[enum_dispatch(future::Future)]
+enum AnyTask {
+    Ready,
+    Doubled,
+}
+
+#[test]
+fn future() {
+    let task: Task = Ready(3).into();
+    assert_eq!(smol::block_on(task), 3);
+    let task: AnyTask = Doubled(Ready(4)).into();
+    assert!(matches!(smol::block_on(task), AnyTaskFutureOutput::Doubled(8)));
+}
+
+mod user_defined {
+    use enum_dispatch::enum_dispatch;
+
+    // Bare names refer to user-defined traits, even if they match a trait from the standard library.
+    #[enum_dispatch]
+    pub trait Display {
+        fn show(&self) -> String;
+    }
+
+    pub struct Label(pub &'static str);
+
+    impl Display for Label {
+        fn show(&self) -> String {
+            self.0.to_string()
+        }
+    }
+
+    #[enum_dispatch(Display)]
+    pub enum Widget {
+        Label,
+    }
+}
+
+#[test]
+fn user_defined_trait_with_builtin_name() {
+    use user_defined::Display as _;
+    let widget: user_defined::Widget = user_defined::Label("ok").into();
+    assert_eq!(widget.show(), "ok");
+}
@@ -0,0 +1,65 @@
+use enum_dispatch::enum_dispatch;
+
+mod shapes {
+    use enum_dispatch::enum_dispatch;
+
+    pub struct Circle {
+        pub radius: f32,
+    }
+
+    impl super::Area for Circle {
+        fn area(&self) -> f32 {
+            3.0 * self.radius * self.radius
+        }
+    }
+
+    #[enum_dispatch]
+    pub enum Shape {
+        Circle,
+    }
+}
+
+use shapes::{Circle, Shape};
+
+// Linked before the enum is known to the cache in this module.
+#[enum_dispatch(Shape)]
+trait Area {
+    fn area(&self) -> f32;
+}
+
+struct Square(f32);
+
+impl<T> Perimeter<T> for Square {
+    fn perimeter(&self) -> f32 {
+        4.0 * self.0
+    }
+}
+
+#[enum_dispatch(Perimeter<T>)]
+enum Polygon<T> {
+    Square,
+    #[allow(dead_code)]
+    Phantom(std::marker::PhantomData<T>),
+}
+
+impl<T> Perimeter<T> for std::marker::PhantomData<T> {
+    fn perimeter(&self) -> f32 {
+        0.0
+    }
+}
+
+#[enum_dispatch]
+trait Perimeter<T> {
+    fn perimeter(&self) -> f32;
+}
+
+// Every link above is fulfilled, so this must not report any errors.
+enum_dispatch::check!();
+
+#[test]
+fn main() {
+    let shape: Shape = Circle { radius: 1.0 }.into();
+    assert_eq!(shape.area(), 3.0);
+    let polygon: Polygon<f32> = Square(2.0).into();
+    assert_eq!(polygon.perimeter(), 8.0);
+}
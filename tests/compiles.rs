@@ -177,7 +177,7 @@ fn main() {
     let d_from_c: Result<D, _> = c.try_into();
     assert!(d_from_c.is_err());
     assert_eq!(
-        d_from_c.err().unwrap(),
+        d_from_c.err().unwrap().to_string(),
         "Tried to convert variant C to LetterD"
     );
 }
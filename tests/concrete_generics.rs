@@ -0,0 +1,148 @@
+use enum_dispatch::enum_dispatch;
+
+#[derive(Debug)]
+pub struct Get(&'static str);
+
+#[derive(Debug)]
+pub struct Post(&'static str, u32);
+
+#[enum_dispatch]
+trait Handler<R> {
+    fn handle(&self, request: R) -> String;
+
+    fn handle_all(&self, requests: Vec<R>) -> Vec<String> {
+        requests.into_iter().map(|request| self.handle(request)).collect()
+    }
+}
+
+pub struct Router;
+pub struct Logger;
+
+impl Handler<Get> for Router {
+    fn handle(&self, request: Get) -> String {
+        format!("GET {}", request.0)
+    }
+}
+
+impl Handler<Post> for Router {
+    fn handle(&self, request: Post) -> String {
+        format!("POST {} ({} bytes)", request.0, request.1)
+    }
+}
+
+impl<R: std::fmt::Debug> Handler<R> for Logger {
+    fn handle(&self, request: R) -> String {
+        format!("{:?}", request)
+    }
+}
+
+#[enum_dispatch(Handler<Get>, Handler<Post>)]
+enum AnyHandler {
+    Router,
+    Logger,
+}
+
+#[enum_dispatch]
+trait Encode<T> {
+    fn encode(&self, value: &T) -> Vec<u8>;
+
+    fn placeholder(&self) -> T
+    where
+        T: Default,
+    {
+        T::default()
+    }
+}
+
+pub struct Raw;
+
+impl Encode<Vec<u8>> for Raw {
+    fn encode(&self, value: &Vec<u8>) -> Vec<u8> {
+        value.clone()
+    }
+}
+
+impl Encode<u32> for Raw {
+    fn encode(&self, value: &u32) -> Vec<u8> {
+        value.to_be_bytes().to_vec()
+    }
+
+    fn placeholder(&self) -> u32 {
+        7
+    }
+}
+
+#[enum_dispatch(Encode<Vec<u8>>, Encode<u32>)]
+enum Encoder {
+    Raw,
+}
+
+#[enum_dispatch]
+trait Chunk<const N: usize> {
+    fn chunk(&self) -> [u8; N];
+}
+
+pub struct Zeroes;
+
+impl<const N: usize> Chunk<N> for Zeroes {
+    fn chunk(&self) -> [u8; N] {
+        [0; N]
+    }
+}
+
+#[enum_dispatch(Chunk<4>)]
+enum AnyChunk {
+    Zeroes,
+}
+
+// Type arguments can also refer to the enum's own generic parameters.
+#[enum_dispatch(Handler<Option<T>>)]
+enum Wrapper<T: std::fmt::Debug> {
+    Logger,
+    Other(std::marker::PhantomData<T>),
+}
+
+impl<T> Handler<Option<T>> for std::marker::PhantomData<T> {
+    fn handle(&self, request: Option<T>) -> String {
+        request.map_or("none", |_| "some").to_string()
+    }
+}
+
+#[test]
+fn multiple_instantiations() {
+    let router: AnyHandler = Router.into();
+    let logger: AnyHandler = Logger.into();
+
+    assert_eq!(router.handle(Get("/")), "GET /");
+    assert_eq!(router.handle(Post("/upload", 3)), "POST /upload (3 bytes)");
+    assert_eq!(logger.handle(Get("/")), "Get(\"/\")");
+    assert_eq!(
+        router.handle_all(vec![Get("/a"), Get("/b")]),
+        vec!["GET /a", "GET /b"]
+    );
+}
+
+#[test]
+fn concrete_type_arguments() {
+    let encoder: Encoder = Raw.into();
+
+    assert_eq!(encoder.encode(&vec![1, 2]), vec![1, 2]);
+    assert_eq!(encoder.encode(&1u32), vec![0, 0, 0, 1]);
+    assert_eq!(Encode::<Vec<u8>>::placeholder(&encoder), Vec::<u8>::new());
+    assert_eq!(Encode::<u32>::placeholder(&encoder), 7);
+}
+
+#[test]
+fn concrete_const_argument() {
+    let chunk: AnyChunk = Zeroes.into();
+    assert_eq!(chunk.chunk(), [0; 4]);
+}
+
+#[test]
+fn generic_type_argument() {
+    let logger: Wrapper<u8> = Logger.into();
+    let other: Wrapper<u8> = std::marker::PhantomData.into();
+
+    assert_eq!(logger.handle(Some(1)), "Some(1)");
+    assert_eq!(other.handle(None), "none");
+}
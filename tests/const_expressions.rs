@@ -0,0 +1,98 @@
+use enum_dispatch::enum_dispatch;
+
+type Size = usize;
+
+const SIZE: usize = 2;
+const LEN: Size = 3;
+
+mod consts {
+    pub const WIDTH: super::Size = 5;
+}
+
+#[enum_dispatch]
+trait Buffer<const N: Size> {
+    fn buffer(&self) -> [u8; N];
+
+    fn len(&self) -> usize {
+        N
+    }
+
+    fn padded_len(&self) -> usize {
+        N + 1
+    }
+
+    fn halves(&self) -> (usize, usize) {
+        (N / 2, N - N / 2)
+    }
+}
+
+pub struct Zeroed;
+pub struct Filled(u8);
+
+impl<const N: usize> Buffer<N> for Zeroed {
+    fn buffer(&self) -> [u8; N] {
+        [0; N]
+    }
+}
+
+impl<const N: usize> Buffer<N> for Filled {
+    fn buffer(&self) -> [u8; N] {
+        [self.0; N]
+    }
+}
+
+#[enum_dispatch(Buffer<{ SIZE * 2 }>, Buffer<LEN>, Buffer<consts::WIDTH>)]
+enum AnyBuffer {
+    Zeroed,
+    Filled,
+}
+
+// Linked from the trait, with a const parameter typed through an alias.
+#[enum_dispatch(Grid<N>)]
+trait Cells<const N: Size> {
+    fn cells(&self) -> usize;
+}
+
+pub struct Square<const N: Size>;
+
+impl<const N: Size> Cells<N> for Square<N> {
+    fn cells(&self) -> usize {
+        N * N
+    }
+}
+
+#[enum_dispatch]
+enum Grid<const N: Size> {
+    Square(Square<N>),
+}
+
+#[test]
+fn block_const_argument() {
+    let filled: AnyBuffer = Filled(1).into();
+    let buffer: [u8; 4] = filled.buffer();
+    assert_eq!(buffer, [1; 4]);
+    assert_eq!(Buffer::<4>::len(&filled), 4);
+}
+
+#[test]
+fn const_argument_in_expressions() {
+    let zeroed: AnyBuffer = Zeroed.into();
+    assert_eq!(Buffer::<4>::padded_len(&zeroed), 5);
+    assert_eq!(Buffer::<3>::padded_len(&zeroed), 4);
+    assert_eq!(Buffer::<5>::halves(&zeroed), (2, 3));
+}
+
+#[test]
+fn path_const_arguments() {
+    let zeroed: AnyBuffer = Zeroed.into();
+    let short: [u8; 3] = zeroed.buffer();
+    let long: [u8; 5] = zeroed.buffer();
+    assert_eq!(short, [0; 3]);
+    assert_eq!(long, [0; 5]);
+}
+
+#[test]
+fn aliased_const_parameter() {
+    let grid: Grid<3> = Square.into();
+    assert_eq!(grid.cells(), 9);
+}
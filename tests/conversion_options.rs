@@ -0,0 +1,108 @@
+use enum_dispatch::enum_dispatch;
+use std::convert::TryFrom;
+
+#[enum_dispatch]
+trait Shape {
+    fn area(&self) -> f32;
+    fn scaled(&self, factor: f32) -> Self;
+}
+
+#[derive(Debug, PartialEq)]
+struct Square(f32);
+
+#[derive(Debug, PartialEq)]
+struct Circle(f32);
+
+impl Shape for Square {
+    fn area(&self) -> f32 {
+        self.0 * self.0
+    }
+
+    fn scaled(&self, factor: f32) -> Self {
+        Square(self.0 * factor)
+    }
+}
+
+impl Shape for Circle {
+    fn area(&self) -> f32 {
+        3.0 * self.0 * self.0
+    }
+
+    fn scaled(&self, factor: f32) -> Self {
+        Circle(self.0 * factor)
+    }
+}
+
+// Two variants with the same type.
+#[enum_dispatch(Shape)]
+#[derive(Debug, PartialEq)]
+enum Tile {
+    Floor(Square),
+    #[enum_dispatch(skip_from)]
+    Wall(Square),
+    Circle,
+}
+
+// A generic variant type, which would conflict with the blanket `impl<T> From<T> for T`.
+#[enum_dispatch(Shape, accessors)]
+enum Custom<T: Shape> {
+    #[enum_dispatch(skip_from)]
+    Other(T),
+    Square,
+}
+
+// Hand-written conversions.
+#[enum_dispatch(Shape, no_from, no_try_from)]
+#[derive(Debug, PartialEq)]
+enum Outline {
+    Square,
+    Circle,
+}
+
+impl From<f32> for Outline {
+    fn from(side: f32) -> Self {
+        Outline::Square(Square(side))
+    }
+}
+
+impl TryFrom<Outline> for f32 {
+    type Error = ();
+
+    fn try_from(outline: Outline) -> Result<f32, ()> {
+        match outline {
+            Outline::Square(Square(side)) => Ok(side),
+            Outline::Circle(_) => Err(()),
+        }
+    }
+}
+
+#[test]
+fn duplicate_types() {
+    let floor: Tile = Square(2.0).into();
+    assert_eq!(floor, Tile::Floor(Square(2.0)));
+
+    let wall = Tile::Wall(Square(3.0));
+    assert_eq!(wall.area(), 9.0);
+    assert_eq!(wall.scaled(2.0), Tile::Wall(Square(6.0)));
+    assert!(Square::try_from(wall).is_err());
+    assert_eq!(Square::try_from(floor).ok(), Some(Square(2.0)));
+}
+
+#[test]
+fn generic_variant() {
+    let custom: Custom<Circle> = Custom::Other(Circle(1.0));
+    assert_eq!(custom.area(), 3.0);
+    assert_eq!(custom.as_other(), Some(&Circle(1.0)));
+
+    let square: Custom<Circle> = Square(1.0).into();
+    assert_eq!(square.scaled(3.0).area(), 9.0);
+}
+
+#[test]
+fn custom_conversions() {
+    let outline = Outline::from(2.0);
+    assert_eq!(outline.area(), 4.0);
+    assert_eq!(outline.scaled(2.0), Outline::Square(Square(4.0)));
+    assert_eq!(f32::try_from(outline), Ok(2.0));
+    assert_eq!(f32::try_from(Outline::Circle(Circle(1.0))), Err(()));
+}
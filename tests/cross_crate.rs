@@ -0,0 +1,54 @@
+use enum_dispatch::enum_dispatch;
+use export_upstream::{Handler, Named, Request};
+
+pub struct Echo;
+
+impl Handler for Echo {
+    fn handle(&self, request: &Request) -> String {
+        request.path.clone()
+    }
+}
+
+impl Named for Echo {
+    fn name(&self) -> &'static str {
+        "echo"
+    }
+}
+
+pub struct NotFound;
+
+impl Handler for NotFound {
+    fn handle(&self, request: &Request) -> String {
+        format!("404: {}", request.path)
+    }
+    fn priority(&self) -> u8 {
+        10
+    }
+}
+
+impl Named for NotFound {
+    fn name(&self) -> &'static str {
+        "not found"
+    }
+}
+
+#[enum_dispatch(::export_upstream::Handler, ::export_upstream::Named)]
+enum AnyHandler {
+    Echo,
+    NotFound,
+}
+
+#[test]
+fn main() {
+    let request = Request {
+        path: "/index.html".to_string(),
+    };
+    let handlers: Vec<AnyHandler> = vec![Echo.into(), NotFound.into()];
+
+    assert_eq!(handlers[0].handle(&request), "/index.html");
+    assert_eq!(handlers[1].handle(&request), "404: /index.html");
+    assert_eq!(handlers[0].priority(), 0);
+    assert_eq!(handlers[1].priority(), 10);
+    assert_eq!(handlers[0].name(), "echo");
+    assert_eq!(handlers[1].name(), "not found");
+}
@@ -0,0 +1,176 @@
+use enum_dispatch::enum_dispatch;
+use std::any::Any;
+use std::sync::Arc;
+
+#[enum_dispatch]
+trait Plugin: Any {
+    fn name(&self) -> String;
+    fn run(&mut self, input: u32) -> u32;
+
+    fn describe(&self) -> String {
+        format!("plugin {}", self.name())
+    }
+
+    fn reset(&mut self) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        None
+    }
+
+    fn into_name(self: Box<Self>) -> String {
+        self.name()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Double;
+
+#[derive(Debug, PartialEq)]
+struct Counter(u32);
+
+#[derive(Debug, PartialEq)]
+struct Custom(String);
+
+impl Plugin for Double {
+    fn name(&self) -> String {
+        "double".to_string()
+    }
+
+    fn run(&mut self, input: u32) -> u32 {
+        input * 2
+    }
+}
+
+impl Plugin for Counter {
+    fn name(&self) -> String {
+        "counter".to_string()
+    }
+
+    fn run(&mut self, input: u32) -> u32 {
+        self.0 += input;
+        self.0
+    }
+}
+
+impl Plugin for Custom {
+    fn name(&self) -> String {
+        self.0.clone()
+    }
+
+    fn run(&mut self, input: u32) -> u32 {
+        input + 1
+    }
+
+    fn describe(&self) -> String {
+        format!("custom plugin {}", self.0)
+    }
+
+    fn into_name(self: Box<Self>) -> String {
+        self.0
+    }
+}
+
+#[enum_dispatch(Plugin, accessors)]
+enum AnyPlugin {
+    Double,
+    Counter,
+    #[enum_dispatch(dynamic(downcast))]
+    Other(Box<dyn Plugin>),
+}
+
+#[enum_dispatch]
+trait Named {
+    fn name(&self) -> &str;
+    fn rename(&mut self, name: &str);
+}
+
+struct Fixed;
+
+impl Named for Fixed {
+    fn name(&self) -> &str {
+        "fixed"
+    }
+    fn rename(&mut self, _name: &str) {}
+}
+
+impl Named for String {
+    fn name(&self) -> &str {
+        self
+    }
+    fn rename(&mut self, name: &str) {
+        *self = name.to_string();
+    }
+}
+
+#[enum_dispatch(Named)]
+enum AnyNamed {
+    Fixed,
+    #[enum_dispatch(dynamic)]
+    Shared(Arc<dyn Named>),
+}
+
+#[test]
+fn dispatch_through_trait_object() {
+    let mut double: AnyPlugin = Double.into();
+    let boxed: Box<dyn Plugin> = Box::new(Custom("custom".to_string()));
+    let mut custom: AnyPlugin = boxed.into();
+
+    assert_eq!(double.name(), "double");
+    assert_eq!(double.run(3), 6);
+    assert_eq!(custom.name(), "custom");
+    assert_eq!(custom.run(3), 4);
+    assert_eq!(custom.describe(), "custom plugin custom");
+    assert!(custom.is_other());
+    assert_eq!(custom.as_other().map(|plugin| plugin.name()), Some("custom".to_string()));
+}
+
+#[test]
+fn boxed_receiver_through_trait_object() {
+    let boxed: Box<dyn Plugin> = Box::new(Custom("custom".to_string()));
+    let custom = Box::new(AnyPlugin::from(boxed));
+    let double = Box::new(AnyPlugin::from(Double));
+
+    assert_eq!(custom.into_name(), "custom");
+    assert_eq!(double.into_name(), "double");
+}
+
+#[test]
+fn downcast_into_builtin() {
+    let boxed: Box<dyn Plugin> = Box::new(Counter(5));
+    let mut counter = AnyPlugin::from(boxed).into_builtin();
+    assert!(counter.is_counter());
+    assert_eq!(counter.run(1), 6);
+    assert_eq!(counter.as_counter(), Some(&Counter(6)));
+
+    let boxed: Box<dyn Plugin> = Box::new(Custom("custom".to_string()));
+    let custom = AnyPlugin::from(boxed).into_builtin();
+    assert!(custom.is_other());
+
+    let double = AnyPlugin::from(Double).into_builtin();
+    assert!(double.is_double());
+}
+
+#[test]
+#[should_panic(expected = "`reset` cannot be called on the trait object held by the dynamic variant `Other`")]
+fn sized_methods_panic() {
+    let boxed: Box<dyn Plugin> = Box::new(Double);
+    let _ = AnyPlugin::from(boxed).reset();
+}
+
+#[test]
+fn shared_trait_object() {
+    let shared: Arc<dyn Named> = Arc::new("shared".to_string());
+    let shared = AnyNamed::from(shared);
+    let fixed = AnyNamed::from(Fixed);
+
+    assert_eq!(shared.name(), "shared");
+    assert_eq!(fixed.name(), "fixed");
+}
+
+#[test]
+#[should_panic(expected = "`rename` cannot be called on the trait object held by the dynamic variant `Shared`")]
+fn shared_trait_object_mutable_methods_panic() {
+    let shared: Arc<dyn Named> = Arc::new("shared".to_string());
+    AnyNamed::from(shared).rename("renamed");
+}
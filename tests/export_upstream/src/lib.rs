@@ -0,0 +1,21 @@
+//! Helper crate for the `cross_crate` test, exporting a trait to be implemented by an enum in
+//! another crate.
+
+use enum_dispatch::enum_dispatch;
+
+pub struct Request {
+    pub path: String,
+}
+
+#[enum_dispatch(export)]
+pub trait Handler {
+    fn handle(&self, request: &Request) -> String;
+    fn priority(&self) -> u8 {
+        0
+    }
+}
+
+#[enum_dispatch(export)]
+pub trait Named {
+    fn name(&self) -> &'static str;
+}
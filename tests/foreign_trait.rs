@@ -0,0 +1,83 @@
+use enum_dispatch::{enum_dispatch, foreign_trait};
+
+/// Stands in for a third-party crate whose traits can't be annotated.
+mod vendor {
+    pub trait Handler {
+        fn handle(&self, request: &str) -> String;
+        fn ready(&self) -> bool {
+            true
+        }
+    }
+
+    pub trait Service {
+        fn call(&mut self, request: String) -> usize;
+    }
+}
+
+use vendor::{Handler, Service};
+
+foreign_trait! {
+    trait vendor::Handler {
+        fn handle(&self, request: &str) -> String;
+        fn ready(&self) -> bool;
+    }
+}
+
+struct Upper;
+
+impl Handler for Upper {
+    fn handle(&self, request: &str) -> String {
+        request.to_uppercase()
+    }
+}
+
+struct Offline;
+
+impl Handler for Offline {
+    fn handle(&self, _request: &str) -> String {
+        String::new()
+    }
+    fn ready(&self) -> bool {
+        false
+    }
+}
+
+#[enum_dispatch(Handler)]
+enum AnyHandler {
+    Upper,
+    Offline,
+}
+
+struct Counter(usize);
+
+impl Service for Counter {
+    fn call(&mut self, request: String) -> usize {
+        self.0 += request.len();
+        self.0
+    }
+}
+
+#[enum_dispatch(Service)]
+enum AnyService {
+    Counter,
+}
+
+// Mirrors can also be declared after the enums that link to them.
+foreign_trait! {
+    trait vendor::Service {
+        fn call(&mut self, request: String) -> usize;
+    }
+}
+
+#[test]
+fn main() {
+    let upper: AnyHandler = Upper.into();
+    let offline: AnyHandler = Offline.into();
+    assert_eq!(upper.handle("abc"), "ABC");
+    assert!(upper.ready());
+    assert!(!offline.ready());
+
+    let mut service: AnyService = Counter(0).into();
+    assert_eq!(service.call("ab".to_string()), 2);
+    assert_eq!(service.call("cde".to_string()), 5);
+}
@@ -0,0 +1,92 @@
+use enum_dispatch::enum_dispatch;
+use std::str::FromStr;
+
+#[enum_dispatch]
+trait Shape {
+    fn area(&self) -> f64;
+}
+
+struct Square(f64);
+struct Circle(f64);
+#[cfg(any())]
+struct Hexagon(f64);
+
+impl Shape for Square {
+    fn area(&self) -> f64 {
+        self.0 * self.0
+    }
+}
+
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        3.0 * self.0 * self.0
+    }
+}
+
+#[enum_dispatch(Shape, kind)]
+enum AnyShape {
+    Square,
+    #[cfg(any())]
+    Hexagon,
+    Circle,
+}
+
+#[test]
+fn main() {
+    let square: AnyShape = Square(2.0).into();
+    let circle: AnyShape = Circle(1.0).into();
+
+    assert_eq!(square.kind(), AnyShapeKind::Square);
+    assert_eq!(circle.kind(), AnyShapeKind::Circle);
+    assert_eq!(square.variant_name(), "Square");
+    assert_eq!(circle.variant_name(), "Circle");
+    assert_eq!(square.area(), 4.0);
+
+    assert_eq!(AnyShape::VARIANT_NAMES, &["Square", "Circle"]);
+    assert_eq!(AnyShape::VARIANT_COUNT, 2);
+
+    assert_eq!(AnyShapeKind::Circle.name(), "Circle");
+    assert_eq!(AnyShapeKind::Square.to_string(), "Square");
+    assert_eq!(AnyShapeKind::from_str("Circle"), Ok(AnyShapeKind::Circle));
+    assert_eq!("Hexagon".parse::<AnyShapeKind>(), Err(ParseAnyShapeKindError));
+    assert_eq!(
+        ParseAnyShapeKindError.to_string(),
+        "unknown AnyShape variant name"
+    );
+}
+
+#[enum_dispatch]
+trait Named {
+    fn name(&self) -> &'static str;
+}
+
+impl Named for Square {
+    fn name(&self) -> &'static str {
+        "square"
+    }
+}
+
+impl Named for Circle {
+    fn name(&self) -> &'static str {
+        "circle"
+    }
+}
+
+// Options from all attributes on the enum apply together, and the `Kind` enum is only generated
+// once.
+#[enum_dispatch(Shape, kind)]
+#[enum_dispatch(Named, accessors)]
+enum NamedShape {
+    Square,
+    Circle,
+}
+
+#[test]
+fn multiple_attributes() {
+    let circle: NamedShape = Circle(1.0).into();
+
+    assert_eq!(circle.kind(), NamedShapeKind::Circle);
+    assert!(circle.is_circle());
+    assert_eq!(circle.name(), "circle");
+    assert_eq!(circle.area(), 3.0);
+}
@@ -0,0 +1,120 @@
+use enum_dispatch::enum_dispatch;
+use std::convert::TryFrom;
+
+#[enum_dispatch]
+trait Decode<'de> {
+    fn payload(&self) -> &'de [u8];
+
+    fn size(&self) -> usize {
+        self.payload().len()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Text<'a>(&'a [u8]);
+
+#[derive(Debug, PartialEq)]
+struct Binary<'b> {
+    bytes: &'b [u8],
+}
+
+impl<'a> Decode<'a> for Text<'a> {
+    fn payload(&self) -> &'a [u8] {
+        self.0
+    }
+}
+
+impl<'b> Decode<'b> for Binary<'b> {
+    fn payload(&self) -> &'b [u8] {
+        &self.bytes[1..]
+    }
+}
+
+#[enum_dispatch(Decode<'a>)]
+#[derive(Debug, PartialEq)]
+enum Frame<'a> {
+    Text(Text<'a>),
+    Binary(Binary<'a>),
+}
+
+#[enum_dispatch(Decode<'static>)]
+enum StaticFrame {
+    Text(Text<'static>),
+}
+
+// Linked from the trait, before the enum is defined.
+#[enum_dispatch(Packet<'p>)]
+trait Inspect<'p> {
+    fn header(&self) -> &'p str;
+}
+
+struct Ping<'x>(&'x str);
+
+impl<'x> Inspect<'x> for Ping<'x> {
+    fn header(&self) -> &'x str {
+        self.0
+    }
+}
+
+#[enum_dispatch]
+enum Packet<'x> {
+    Ping(Ping<'x>),
+}
+
+// Links without arguments keep the lifetime parameters of the linked item.
+#[enum_dispatch(Envelope)]
+trait Seal {
+    fn sealed(&self) -> bool;
+}
+
+impl<'x> Seal for Ping<'x> {
+    fn sealed(&self) -> bool {
+        !self.0.is_empty()
+    }
+}
+
+#[enum_dispatch]
+enum Envelope<'a> {
+    Ping(Ping<'a>),
+}
+
+fn decode_first(input: &[u8]) -> &[u8] {
+    let frame: Frame = Text(input).into();
+    frame.payload()
+}
+
+#[test]
+fn lifetime_linked_from_enum() {
+    let data = vec![1, 2, 3];
+    let text: Frame = Text(&data).into();
+    let binary: Frame = Binary { bytes: &data }.into();
+
+    assert_eq!(text.payload(), &[1, 2, 3]);
+    assert_eq!(binary.payload(), &[2, 3]);
+    assert_eq!(binary.size(), 2);
+    assert_eq!(decode_first(&data), &[1, 2, 3]);
+    assert_eq!(Text::try_from(text).ok(), Some(Text(&data)));
+}
+
+#[test]
+fn static_lifetime_argument() {
+    let frame: StaticFrame = Text(b"static").into();
+    assert_eq!(frame.payload(), b"static");
+}
+
+#[test]
+fn lifetime_linked_from_trait() {
+    let name = String::from("ping");
+    let header = {
+        let packet: Packet = Ping(&name).into();
+        packet.header()
+    };
+    assert_eq!(header, "ping");
+}
+
+#[test]
+fn lifetime_arguments_left_out() {
+    let name = String::from("sealed");
+    let envelope: Envelope = Ping(&name).into();
+    assert!(envelope.sealed());
+}
@@ -0,0 +1,134 @@
+use enum_dispatch::enum_dispatch;
+use std::convert::TryFrom;
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Metadata {
+    label: &'static str,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Handle(u32);
+
+#[derive(Debug, PartialEq)]
+pub struct Circle {
+    radius: f32,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Square {
+    side: f32,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Triangle {
+    base: f32,
+    height: f32,
+}
+
+impl Shape for Circle {
+    fn area(&self) -> f32 {
+        3.0 * self.radius * self.radius
+    }
+
+    fn scaled(&self, factor: f32) -> Self {
+        Circle {
+            radius: self.radius * factor,
+        }
+    }
+}
+
+impl Shape for Square {
+    fn area(&self) -> f32 {
+        self.side * self.side
+    }
+
+    fn scaled(&self, factor: f32) -> Self {
+        Square {
+            side: self.side * factor,
+        }
+    }
+}
+
+impl Shape for Triangle {
+    fn area(&self) -> f32 {
+        self.base * self.height / 2.0
+    }
+
+    fn scaled(&self, factor: f32) -> Self {
+        Triangle {
+            base: self.base * factor,
+            height: self.height * factor,
+        }
+    }
+}
+
+// Defined before the trait, so that the enum goes through the cache.
+#[enum_dispatch(accessors)]
+#[derive(Debug, PartialEq)]
+pub enum AnyShape {
+    Circle(#[dispatch] Circle, Metadata),
+    Square {
+        #[dispatch]
+        shape: Square,
+        id: u32,
+    },
+    Triangle {
+        shape: Triangle,
+    },
+    // Skipped, since `Circle` is already used by another variant.
+    #[enum_dispatch(skip_from)]
+    Tracked(Metadata, #[dispatch] Circle, Handle),
+}
+
+#[enum_dispatch(AnyShape)]
+trait Shape {
+    fn area(&self) -> f32;
+    fn scaled(&self, factor: f32) -> Self;
+}
+
+#[test]
+fn dispatch_to_marked_field() {
+    let circle = AnyShape::Circle(Circle { radius: 1.0 }, Metadata { label: "circle" });
+    let square = AnyShape::Square {
+        shape: Square { side: 2.0 },
+        id: 7,
+    };
+    let triangle = AnyShape::Triangle {
+        shape: Triangle {
+            base: 2.0,
+            height: 3.0,
+        },
+    };
+    let tracked = AnyShape::Tracked(Metadata::default(), Circle { radius: 2.0 }, Handle(1));
+
+    assert_eq!(circle.area(), 3.0);
+    assert_eq!(square.area(), 4.0);
+    assert_eq!(triangle.area(), 3.0);
+    assert_eq!(tracked.area(), 12.0);
+}
+
+#[test]
+fn conversions_default_other_fields() {
+    let circle: AnyShape = Circle { radius: 1.0 }.into();
+    assert_eq!(circle, AnyShape::Circle(Circle { radius: 1.0 }, Metadata::default()));
+
+    let square: AnyShape = Square { side: 1.0 }.into();
+    assert_eq!(
+        square.scaled(3.0),
+        AnyShape::Square {
+            shape: Square { side: 3.0 },
+            id: 0,
+        }
+    );
+
+    let square = AnyShape::Square {
+        shape: Square { side: 2.0 },
+        id: 7,
+    };
+    assert_eq!(square.as_square(), Some(&Square { side: 2.0 }));
+    assert_eq!(Square::try_from(square).ok(), Some(Square { side: 2.0 }));
+
+    let tracked = AnyShape::Tracked(Metadata::default(), Circle { radius: 2.0 }, Handle(1));
+    assert_eq!(tracked.as_tracked(), Some(&Circle { radius: 2.0 }));
+    assert!(Circle::try_from(tracked).is_err());
+}
@@ -82,7 +82,10 @@ fn main() {
 
     let b_from_c: Result<B, _> = c.try_into();
     assert!(b_from_c.is_err());
-    assert_eq!(b_from_c.err().unwrap(), "Tried to convert variant C to B");
+    assert_eq!(
+        b_from_c.err().unwrap().to_string(),
+        "Tried to convert variant C to B"
+    );
 
     let a_from_a: Result<A, _> = a.try_into();
     assert!(a_from_a.is_ok());
@@ -102,7 +105,7 @@ fn main() {
     let b_from_c_sl: Result<B, _> = c_sl.try_into();
     assert!(b_from_c_sl.is_err());
     assert_eq!(
-        b_from_c_sl.err().unwrap(),
+        b_from_c_sl.err().unwrap().to_string(),
         "Tried to convert variant C to B"
     );
 
@@ -0,0 +1,93 @@
+use enum_dispatch::enum_dispatch;
+
+mod engine {
+    pub mod ops {
+        use enum_dispatch::enum_dispatch;
+
+        #[enum_dispatch(module = crate::engine::ops)]
+        pub trait Apply {
+            fn apply(&self, value: u32) -> u32;
+        }
+    }
+
+    pub mod filters {
+        use enum_dispatch::enum_dispatch;
+
+        #[enum_dispatch(module = crate::engine::filters)]
+        pub trait Apply {
+            fn apply(&self, value: u32) -> bool;
+        }
+    }
+}
+
+mod runtime {
+    use enum_dispatch::enum_dispatch;
+
+    pub struct Double;
+    pub struct Even;
+
+    impl crate::engine::ops::Apply for Double {
+        fn apply(&self, value: u32) -> u32 {
+            value * 2
+        }
+    }
+
+    impl crate::engine::ops::Apply for Even {
+        fn apply(&self, value: u32) -> u32 {
+            value
+        }
+    }
+
+    impl crate::engine::filters::Apply for Double {
+        fn apply(&self, _value: u32) -> bool {
+            true
+        }
+    }
+
+    impl crate::engine::filters::Apply for Even {
+        fn apply(&self, value: u32) -> bool {
+            value & 1 == 0
+        }
+    }
+
+    #[enum_dispatch(crate::engine::ops::Apply)]
+    pub enum Op {
+        Double,
+        Even,
+    }
+
+    #[enum_dispatch(crate::engine::filters::Apply)]
+    pub enum Filter {
+        Double,
+        Even,
+    }
+}
+
+// The enum is linked from the trait side, so the impl is generated here and needs the full path
+// to the enum.
+#[enum_dispatch(crate::runtime::Op)]
+trait Describe {
+    fn describe(&self) -> &'static str;
+}
+
+impl Describe for runtime::Double {
+    fn describe(&self) -> &'static str {
+        "double"
+    }
+}
+
+impl Describe for runtime::Even {
+    fn describe(&self) -> &'static str {
+        "even"
+    }
+}
+
+#[test]
+fn main() {
+    let op: runtime::Op = runtime::Double.into();
+    let filter: runtime::Filter = runtime::Even.into();
+
+    assert_eq!(engine::ops::Apply::apply(&op, 3), 6);
+    assert!(!engine::filters::Apply::apply(&filter, 3));
+    assert_eq!(op.describe(), "double");
+}
@@ -0,0 +1,94 @@
+use enum_dispatch::enum_dispatch;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::Arc;
+
+#[enum_dispatch]
+trait Builder {
+    fn build(self: Box<Self>) -> String;
+    fn describe(self: Rc<Self>) -> String {
+        format!("builder ({} references)", Rc::strong_count(&self))
+    }
+    fn share(self: Arc<Self>, suffix: &str) -> String {
+        format!("shared{}", suffix)
+    }
+    fn poke(self: Pin<&mut Self>) -> u32;
+    fn peek(self: Pin<&Self>) -> u32;
+}
+
+struct Request {
+    url: String,
+    pokes: u32,
+}
+
+impl Builder for Request {
+    fn build(self: Box<Self>) -> String {
+        format!("GET {}", self.url)
+    }
+    fn poke(self: Pin<&mut Self>) -> u32 {
+        let this = self.get_mut();
+        this.pokes += 1;
+        this.pokes
+    }
+    fn peek(self: Pin<&Self>) -> u32 {
+        self.pokes
+    }
+}
+
+struct Empty;
+
+impl Builder for Empty {
+    fn build(self: Box<Self>) -> String {
+        String::new()
+    }
+    fn poke(self: Pin<&mut Self>) -> u32 {
+        0
+    }
+    fn peek(self: Pin<&Self>) -> u32 {
+        0
+    }
+}
+
+#[enum_dispatch(Builder)]
+enum AnyBuilder {
+    Request,
+    Empty,
+}
+
+fn request() -> AnyBuilder {
+    Request {
+        url: "/index.html".to_string(),
+        pokes: 0,
+    }
+    .into()
+}
+
+#[test]
+fn boxed() {
+    assert_eq!(Box::new(request()).build(), "GET /index.html");
+    assert_eq!(Box::new(AnyBuilder::from(Empty)).build(), "");
+}
+
+#[test]
+fn reference_counted() {
+    // The enum uses the trait's default implementation, so the shared pointer is not cloned.
+    let shared = Rc::new(request());
+    let _other = Rc::clone(&shared);
+    assert_eq!(shared.describe(), "builder (2 references)");
+    assert_eq!(Arc::new(request()).share("?q=1"), "shared?q=1");
+}
+
+#[test]
+fn pinned() {
+    let mut builder = request();
+    let mut pinned = Pin::new(&mut builder);
+    assert_eq!(pinned.as_mut().poke(), 1);
+    assert_eq!(pinned.as_mut().poke(), 2);
+    assert_eq!(pinned.as_ref().peek(), 2);
+}
+
+#[test]
+fn pinned_unpin_follows_variants() {
+    fn assert_unpin<T: Unpin>() {}
+    assert_unpin::<AnyBuilder>();
+}
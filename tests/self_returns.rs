@@ -0,0 +1,110 @@
+use enum_dispatch::enum_dispatch;
+
+#[derive(Debug, PartialEq)]
+pub struct Idle;
+
+#[derive(Debug, PartialEq)]
+pub struct Running(u8);
+
+#[derive(Debug, PartialEq)]
+pub struct StepError(&'static str);
+
+#[enum_dispatch]
+trait State: Sized {
+    fn step(self) -> Option<Self>;
+    fn try_step(self) -> Result<Self, StepError>;
+    fn split(&self) -> Vec<Self>;
+    fn pair(&self) -> (Self, u8);
+    fn nested(&self) -> Option<Result<[Self; 2], StepError>>;
+    fn parse(input: &str) -> Result<Self, StepError>;
+}
+
+impl State for Idle {
+    fn step(self) -> Option<Self> {
+        None
+    }
+    fn try_step(self) -> Result<Self, StepError> {
+        Err(StepError("idle"))
+    }
+    fn split(&self) -> Vec<Self> {
+        vec![Idle, Idle]
+    }
+    fn pair(&self) -> (Self, u8) {
+        (Idle, 0)
+    }
+    fn nested(&self) -> Option<Result<[Self; 2], StepError>> {
+        None
+    }
+    fn parse(input: &str) -> Result<Self, StepError> {
+        match input {
+            "idle" => Ok(Idle),
+            _ => Err(StepError("unknown")),
+        }
+    }
+}
+
+impl State for Running {
+    fn step(self) -> Option<Self> {
+        self.0.checked_sub(1).map(Running)
+    }
+    fn try_step(self) -> Result<Self, StepError> {
+        Ok(Running(self.0 + 1))
+    }
+    fn split(&self) -> Vec<Self> {
+        (0..self.0).map(Running).collect()
+    }
+    fn pair(&self) -> (Self, u8) {
+        (Running(self.0), self.0)
+    }
+    fn nested(&self) -> Option<Result<[Self; 2], StepError>> {
+        Some(Ok([Running(self.0), Running(self.0 * 2)]))
+    }
+    fn parse(input: &str) -> Result<Self, StepError> {
+        input.parse().map(Running).map_err(|_| StepError("not a number"))
+    }
+}
+
+#[enum_dispatch(State, static_default = Running)]
+#[derive(Debug, PartialEq)]
+enum Machine {
+    Idle,
+    Running,
+}
+
+#[test]
+fn main() {
+    let machine: Machine = Running(1).into();
+    assert_eq!(machine.step(), Some(Machine::Running(Running(0))));
+    let machine: Machine = Idle.into();
+    assert_eq!(machine.step(), None);
+
+    let machine: Machine = Running(4).into();
+    assert_eq!(machine.try_step(), Ok(Machine::Running(Running(5))));
+    let machine: Machine = Idle.into();
+    assert_eq!(machine.try_step(), Err(StepError("idle")));
+
+    let machine: Machine = Running(2).into();
+    assert_eq!(
+        machine.split(),
+        vec![Machine::Running(Running(0)), Machine::Running(Running(1))]
+    );
+    assert_eq!(machine.pair(), (Machine::Running(Running(2)), 2));
+    assert_eq!(
+        machine.nested(),
+        Some(Ok([Machine::Running(Running(2)), Machine::Running(Running(4))]))
+    );
+    assert_eq!(Idle.into_machine().nested(), None);
+
+    assert_eq!(<Machine as State>::parse("3"), Ok(Machine::Running(Running(3))));
+    assert_eq!(<Machine as State>::parse("x"), Err(StepError("not a number")));
+}
+
+trait IntoMachine {
+    fn into_machine(self) -> Machine;
+}
+
+impl<T: Into<Machine>> IntoMachine for T {
+    fn into_machine(self) -> Machine {
+        self.into()
+    }
+}
@@ -0,0 +1,110 @@
+use enum_dispatch::enum_dispatch;
+
+pub struct Config {
+    scale: f64,
+}
+
+#[enum_dispatch]
+trait Knob {
+    fn new(cfg: &Config) -> Self;
+    fn describe() -> &'static str;
+    fn get_value(&self) -> f64;
+}
+
+struct LinearKnob {
+    position: f64,
+}
+
+struct LogarithmicKnob {
+    position: f64,
+}
+
+impl Knob for LinearKnob {
+    fn new(cfg: &Config) -> Self {
+        LinearKnob {
+            position: cfg.scale,
+        }
+    }
+
+    fn describe() -> &'static str {
+        "linear"
+    }
+
+    fn get_value(&self) -> f64 {
+        self.position
+    }
+}
+
+impl Knob for LogarithmicKnob {
+    fn new(cfg: &Config) -> Self {
+        LogarithmicKnob {
+            position: cfg.scale,
+        }
+    }
+
+    fn describe() -> &'static str {
+        "logarithmic"
+    }
+
+    fn get_value(&self) -> f64 {
+        (self.position + 1.).log2()
+    }
+}
+
+#[enum_dispatch(Knob, kind, static_default = LinearKnob)]
+enum AnyKnob {
+    LinearKnob,
+    LogarithmicKnob,
+}
+
+#[test]
+fn main() {
+    let cfg = Config { scale: 3. };
+
+    let default = AnyKnob::new(&cfg);
+    assert_eq!(default.get_value(), 3.);
+    assert_eq!(AnyKnob::describe(), "linear");
+
+    let log = AnyKnob::new_for(AnyKnobKind::LogarithmicKnob, &cfg);
+    assert_eq!(log.get_value(), 2.);
+    assert_eq!(AnyKnob::describe_for(AnyKnobKind::LinearKnob), "linear");
+    assert_eq!(AnyKnob::describe_for(AnyKnobKind::LogarithmicKnob), "logarithmic");
+}
+
+#[enum_dispatch]
+trait Named {
+    fn name(&self) -> &'static str;
+    fn category() -> &'static str {
+        "knob"
+    }
+}
+
+impl Named for LinearKnob {
+    fn name(&self) -> &'static str {
+        "linear"
+    }
+    fn category() -> &'static str {
+        "linear knob"
+    }
+}
+
+impl Named for LogarithmicKnob {
+    fn name(&self) -> &'static str {
+        "logarithmic"
+    }
+}
+
+#[enum_dispatch(Named)]
+enum NamedKnob {
+    LinearKnob,
+    LogarithmicKnob,
+}
+
+#[test]
+fn default_static_method() {
+    // Without a default variant, the enum keeps the trait's default implementation.
+    assert_eq!(NamedKnob::category(), "knob");
+    assert_eq!(LinearKnob::category(), "linear knob");
+    let knob: NamedKnob = LinearKnob { position: 0. }.into();
+    assert_eq!(knob.name(), "linear");
+}
@@ -0,0 +1,151 @@
+use enum_dispatch::enum_dispatch;
+use std::convert::TryFrom;
+use std::error::Error;
+
+#[enum_dispatch]
+trait Describe {
+    fn describe(&self) -> String;
+}
+
+#[derive(Debug, PartialEq)]
+struct Label(String);
+
+#[derive(Debug, PartialEq)]
+struct Count<T>(T);
+
+impl Describe for Label {
+    fn describe(&self) -> String {
+        self.0.clone()
+    }
+}
+
+impl<T: std::fmt::Display> Describe for Count<T> {
+    fn describe(&self) -> String {
+        format!("{} items", self.0)
+    }
+}
+
+#[enum_dispatch(Describe, try_from_error)]
+#[derive(Debug, PartialEq)]
+enum Item<T: std::fmt::Display> {
+    Label,
+    Count(Count<T>),
+}
+
+#[enum_dispatch(Describe)]
+enum LegacyItem {
+    Label,
+    Count(Count<u8>),
+}
+
+#[enum_dispatch(Describe, kind, try_from_error)]
+enum KindedItem {
+    Label,
+    Count(Count<u8>),
+}
+
+// Enums with the same name in different modules each get their own error type, regardless of the
+// `module` argument telling their conversions apart.
+mod first {
+    use super::{Count, Describe, Label};
+    use enum_dispatch::enum_dispatch;
+
+    #[enum_dispatch(Describe, try_from_error, module = crate::first)]
+    pub(super) enum Shared {
+        Label,
+        Count(Count<u8>),
+    }
+}
+
+mod second {
+    use super::{Count, Describe, Label};
+    use enum_dispatch::enum_dispatch;
+
+    #[enum_dispatch(Describe, try_from_error, module = crate::second)]
+    pub(super) enum Shared {
+        Label,
+        Count(Count<u16>),
+    }
+}
+
+#[test]
+fn failed_conversion_keeps_value() {
+    let item: Item<u32> = Count(3).into();
+
+    let error = Label::try_from(item).unwrap_err();
+    assert_eq!(error.expected, "Label");
+    assert_eq!(error.found, "Count");
+    assert_eq!(error.to_string(), "Tried to convert variant Count to Label");
+    assert!(error.source().is_none());
+    assert_eq!(
+        format!("{:?}", error),
+        "ItemTryFromError { expected: \"Label\", found: \"Count\", .. }"
+    );
+
+    let item = error.value;
+    assert_eq!(item.describe(), "3 items");
+    assert_eq!(Count::try_from(item).ok(), Some(Count(3)));
+}
+
+#[test]
+fn found_kind() {
+    let item: KindedItem = Count(3).into();
+
+    let error = Label::try_from(item).unwrap_err();
+    assert_eq!(error.expected, "Label");
+    assert_eq!(error.found, KindedItemKind::Count);
+    assert_eq!(error.to_string(), "Tried to convert variant Count to Label");
+    assert_eq!(error.value.kind(), KindedItemKind::Count);
+}
+
+#[test]
+fn boxed_error() {
+    fn convert(item: Item<u32>) -> Result<Label, Box<dyn Error>> {
+        Ok(Label::try_from(item)?)
+    }
+
+    assert!(convert(Count(1).into()).is_err());
+    assert!(convert(Label("label".to_string()).into()).is_ok());
+}
+
+#[test]
+fn str_error() {
+    let item: LegacyItem = Count(3).into();
+    assert_eq!(
+        Label::try_from(item).err(),
+        Some("Tried to convert variant Count to Label")
+    );
+}
+
+#[test]
+fn same_named_enums() {
+    let first: first::Shared = Count(1u8).into();
+    let second: second::Shared = Count(2u16).into();
+
+    let first::SharedTryFromError { value, .. } = Label::try_from(first).unwrap_err();
+    assert_eq!(value.describe(), "1 items");
+    let second::SharedTryFromError { value, .. } = Label::try_from(second).unwrap_err();
+    assert_eq!(value.describe(), "2 items");
+}
+
+#[enum_dispatch(Describe, kind, try_from_error, accessors)]
+enum RefItem {
+    Label,
+    Count(Count<u8>),
+}
+
+#[test]
+fn reference_conversions() {
+    let mut item: RefItem = Label("label".to_string()).into();
+
+    let error = <&Count<u8>>::try_from(&item).unwrap_err();
+    assert_eq!(error.expected, "Count");
+    assert_eq!(error.found, RefItemKind::Label);
+    assert_eq!(error.to_string(), "Tried to convert variant Label to Count");
+    assert_eq!(error.value.describe(), "label");
+
+    let error = <&mut Count<u8>>::try_from(&mut item).unwrap_err();
+    *error.value = Count(4).into();
+    assert_eq!(item.as_count(), Some(&Count(4)));
+    assert!(item.variant::<Label>().is_none());
+}
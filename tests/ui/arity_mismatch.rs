@@ -0,0 +1,33 @@
+use enum_dispatch::enum_dispatch;
+
+#[enum_dispatch]
+trait Convert<T> {
+    fn convert(&self) -> T;
+}
+
+pub struct Number;
+
+impl Convert<u8> for Number {
+    fn convert(&self) -> u8 {
+        0
+    }
+}
+
+// Linked after the trait was defined, so the mismatch is reported at the link.
+#[enum_dispatch(Convert)]
+enum AnyNumber {
+    Number,
+}
+
+// Linked before the enum is defined, so the mismatch is reported at the enum.
+#[enum_dispatch(Fixed<u8>)]
+trait Describe {
+    fn describe(&self) -> String;
+}
+
+#[enum_dispatch]
+enum Fixed {
+    Number,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use enum_dispatch::enum_dispatch;
+
+#[enum_dispatch]
+enum AnyShape {}
+
+// Every problem with the arguments is reported in a single build.
+#[enum_dispatch(AnyShape::<>, kind, Generic<Vec<u8>>, Bound<Item = u8>)]
+trait Shape {
+    fn area(&self) -> f32;
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+use enum_dispatch::enum_dispatch;
+
+#[enum_dispatch]
+trait Shape {
+    fn area(&self) -> f32;
+}
+
+pub struct Circle;
+pub struct Square;
+
+// Every problem with the enum is reported in a single build.
+#[enum_dispatch(Shape)]
+enum AnyShape {
+    Circle = 1,
+    Square(Square, u8),
+    Both(#[dispatch] Circle, #[dispatch] Square),
+}
+
+fn main() {}
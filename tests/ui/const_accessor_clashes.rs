@@ -0,0 +1,28 @@
+use enum_dispatch::enum_dispatch;
+
+#[enum_dispatch]
+trait Labeled {
+    const LABEL: &'static str = "labeled";
+    const KIND: u8 = 0;
+}
+
+#[enum_dispatch]
+trait Tagged {
+    const LABEL: &'static str = "tagged";
+}
+
+struct Plain;
+
+impl Labeled for Plain {
+    const LABEL: &'static str = "plain";
+}
+
+impl Tagged for Plain {}
+
+// The accessors for `KIND` and `Tagged::LABEL` clash with other generated methods.
+#[enum_dispatch(Labeled, Tagged, kind, const_accessors)]
+enum AnyItem {
+    Plain,
+}
+
+fn main() {}
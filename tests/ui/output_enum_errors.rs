@@ -0,0 +1,36 @@
+use enum_dispatch::enum_dispatch;
+use std::fmt::Display;
+
+#[enum_dispatch]
+trait Produce {
+    type Output: Clone + Display;
+    fn produce(&self) -> Self::Output;
+}
+
+#[enum_dispatch]
+trait Consume {
+    type Input: Clone;
+    fn consume(&self, input: Option<Self::Input>);
+}
+
+pub struct Number;
+
+impl Produce for Number {
+    type Output = u8;
+    fn produce(&self) -> u8 {
+        0
+    }
+}
+
+impl Consume for Number {
+    type Input = u8;
+    fn consume(&self, _input: Option<u8>) {}
+}
+
+// Neither associated type can be implemented with a generated output enum.
+#[enum_dispatch(Produce, Consume)]
+enum AnyNumber {
+    Number,
+}
+
+fn main() {}
@@ -0,0 +1,35 @@
+use enum_dispatch::enum_dispatch;
+use std::pin::Pin;
+
+#[enum_dispatch]
+trait Poll {
+    fn poll(self: Pin<&mut Self>) -> u8;
+}
+
+pub struct Ready;
+
+impl Poll for Ready {
+    fn poll(self: Pin<&mut Self>) -> u8 {
+        0
+    }
+}
+
+// The variants are pinned through the enum, so the enum can't be moved out of a pin by `drop`.
+#[enum_dispatch(Poll, no_try_from)]
+enum AnyPoll {
+    Ready,
+}
+
+impl Drop for AnyPoll {
+    fn drop(&mut self) {}
+}
+
+// Nor can it be `Unpin` unless all of its variants are.
+#[enum_dispatch(Poll)]
+enum OtherPoll {
+    Ready,
+}
+
+impl Unpin for OtherPoll {}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use enum_dispatch::enum_dispatch;
+use std::rc::Rc;
+
+// The active variant can't be moved out of a shared `Rc` without a default implementation, which
+// is reported where the trait is defined.
+#[enum_dispatch]
+trait Describe {
+    fn describe(self: Rc<Self>) -> String;
+}
+
+fn main() {}
@@ -0,0 +1,28 @@
+use enum_dispatch::enum_dispatch;
+
+// Static methods have no `self` argument to match on, so without a default implementation they
+// must be delegated to a variant chosen with `static_default = ...`.
+#[enum_dispatch]
+trait Shape {
+    fn new() -> Self;
+    fn area(&self) -> f32;
+}
+
+struct Square;
+
+impl Shape for Square {
+    fn new() -> Self {
+        Square
+    }
+
+    fn area(&self) -> f32 {
+        1.0
+    }
+}
+
+#[enum_dispatch(Shape)]
+enum AnyShape {
+    Square,
+}
+
+fn main() {}
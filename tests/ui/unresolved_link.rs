@@ -0,0 +1,24 @@
+use enum_dispatch::enum_dispatch;
+
+pub struct Circle;
+
+#[enum_dispatch]
+enum Shape {
+    Circle,
+}
+
+// Misspelled link, suggested despite the transposed characters.
+#[enum_dispatch(Shpae)]
+trait Area {
+    fn area(&self) -> f32;
+}
+
+// Path to a trait in another crate, missing the leading `::`.
+#[enum_dispatch(core_traits::Plugin)]
+enum AnyPlugin {
+    Circle,
+}
+
+enum_dispatch::check!();
+
+fn main() {}
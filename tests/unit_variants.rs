@@ -0,0 +1,89 @@
+use enum_dispatch::enum_dispatch;
+
+#[enum_dispatch]
+trait Shape {
+    const SIDES: u32 = 0;
+
+    fn area(&self) -> f32;
+    fn perimeter(&self) -> f32;
+    fn scaled(&self, factor: f32) -> Self;
+
+    fn name(&self) -> String {
+        "shape".to_string()
+    }
+
+    fn describe(&self, prefix: &str, mut repeat: usize) -> String {
+        let mut description = String::new();
+        while repeat > 0 {
+            description.push_str(prefix);
+            repeat -= 1;
+        }
+        description + &self.name()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Square(f32);
+
+impl Shape for Square {
+    const SIDES: u32 = 4;
+
+    fn area(&self) -> f32 {
+        self.0 * self.0
+    }
+
+    fn perimeter(&self) -> f32 {
+        4.0 * self.0
+    }
+
+    fn scaled(&self, factor: f32) -> Self {
+        Square(self.0 * factor)
+    }
+
+    fn name(&self) -> String {
+        "square".to_string()
+    }
+}
+
+fn no_perimeter(shape: &AnyShape) -> f32 {
+    assert!(shape.is_empty());
+    -1.0
+}
+
+#[enum_dispatch(Shape, kind, accessors, const_accessors)]
+#[derive(Debug, PartialEq)]
+enum AnyShape {
+    Square,
+    #[enum_dispatch(unit)]
+    Nothing,
+    #[enum_dispatch(unit, fallback(perimeter) = no_perimeter)]
+    Empty,
+}
+
+#[test]
+fn unit_variants() {
+    let square: AnyShape = Square(2.0).into();
+    assert_eq!(square.area(), 4.0);
+    assert_eq!(square.describe("-", 2), "--square");
+    assert_eq!(square.sides(), 4);
+
+    let nothing = AnyShape::Nothing;
+    // No default implementation, so the return type's default value is used.
+    assert_eq!(nothing.area(), 0.0);
+    assert_eq!(nothing.perimeter(), 0.0);
+    // Methods returning `Self` return the unit variant itself.
+    assert_eq!(nothing.scaled(2.0), AnyShape::Nothing);
+    // Default implementations are used when they exist.
+    assert_eq!(nothing.name(), "shape");
+    assert_eq!(nothing.describe("+", 3), "+++shape");
+    assert_eq!(nothing.sides(), 0);
+
+    let empty = AnyShape::Empty;
+    assert_eq!(empty.perimeter(), -1.0);
+    assert_eq!(empty.area(), 0.0);
+
+    assert!(nothing.is_nothing());
+    assert!(!square.is_nothing());
+    assert_eq!(nothing.kind(), AnyShapeKind::Nothing);
+    assert_eq!(AnyShape::VARIANT_NAMES, &["Square", "Nothing", "Empty"]);
+}
@@ -0,0 +1,90 @@
+use enum_dispatch::enum_dispatch;
+
+#[enum_dispatch]
+trait Describe {
+    fn describe(&self) -> String;
+}
+
+#[enum_dispatch]
+trait Measure {
+    fn measure(&self) -> usize;
+}
+
+pub struct Plain;
+
+impl Describe for Plain {
+    fn describe(&self) -> String {
+        "plain".to_string()
+    }
+}
+
+impl Measure for Plain {
+    fn measure(&self) -> usize {
+        0
+    }
+}
+
+impl Describe for u8 {
+    fn describe(&self) -> String {
+        format!("byte {}", self)
+    }
+}
+
+impl Measure for String {
+    fn measure(&self) -> usize {
+        self.len()
+    }
+}
+
+pub struct Labeled<T>(T);
+
+impl<T: Describe> Describe for Labeled<T> {
+    fn describe(&self) -> String {
+        format!("labeled {}", self.0.describe())
+    }
+}
+
+// Each impl only requires the variant types to implement its own trait.
+#[enum_dispatch(Describe, Measure)]
+enum Wrapper<T> {
+    Plain,
+    #[enum_dispatch(skip_from)]
+    Custom(T),
+}
+
+#[enum_dispatch(Describe, bound(T: Describe))]
+enum Tagged<T> {
+    Plain,
+    Labeled(Labeled<T>),
+}
+
+#[enum_dispatch(Describe, bound())]
+enum Strict<T: Describe> {
+    Plain,
+    Labeled(Labeled<T>),
+}
+
+#[test]
+fn inferred_bounds() {
+    let byte: Wrapper<u8> = Wrapper::Custom(7);
+    let text: Wrapper<String> = Wrapper::Custom("text".to_string());
+    let plain: Wrapper<String> = Plain.into();
+
+    assert_eq!(byte.describe(), "byte 7");
+    assert_eq!(text.measure(), 4);
+    assert_eq!(plain.measure(), 0);
+}
+
+#[test]
+fn explicit_bounds() {
+    let labeled: Tagged<u8> = Labeled(1).into();
+    assert_eq!(labeled.describe(), "labeled byte 1");
+}
+
+#[test]
+fn suppressed_bounds() {
+    let labeled: Strict<u8> = Labeled(2).into();
+    let plain: Strict<u8> = Plain.into();
+    assert_eq!(labeled.describe(), "labeled byte 2");
+    assert_eq!(plain.describe(), "plain");
+}